@@ -1,11 +1,393 @@
 extern crate alloc;
+use crate::error::TblisError;
+use core::fmt::Debug;
 use core::ptr::NonNull;
+use derive_builder::Builder;
+use std::sync::OnceLock;
+
+/* #region allocator trait */
+
+/// Owned storage of elements, returned by [`TblisAllocator::allocate`].
+///
+/// Storage is either a [`Vec`] of the global allocator (with an offset for alignment, see
+/// [`TblisBuffer::from_vec`]), or raw memory of a custom allocator that is released by a callback
+/// (see [`TblisBuffer::from_raw_parts`]). In both cases, storage is given back to the allocator
+/// that produced it when the buffer is dropped, including buffers of arrays returned to user
+/// (e.g. output of [`tblis_einsum`](crate::einsum_impl::tblis_einsum)).
+pub struct TblisBuffer<T> {
+    /// first element of storage
+    ptr: NonNull<T>,
+    /// number of elements of storage
+    len: usize,
+    /// backing vector of global allocator, if any
+    vec: Option<Vec<T>>,
+    /// callback when storage is released
+    release: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+unsafe impl<T: Send> Send for TblisBuffer<T> {}
+unsafe impl<T: Sync> Sync for TblisBuffer<T> {}
+
+impl<T> Debug for TblisBuffer<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TblisBuffer")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .field("vec", &self.vec.is_some())
+            .finish()
+    }
+}
+
+impl<T> TblisBuffer<T> {
+    /// Buffer of vector (of the global allocator), where `vec[offset..]` is the storage.
+    ///
+    /// # Panics
+    ///
+    /// - If `offset` is larger than length of vector.
+    pub fn from_vec(mut vec: Vec<T>, offset: usize) -> Self {
+        assert!(offset <= vec.len(), "offset {offset} out of vector of length {}", vec.len());
+        let len = vec.len() - offset;
+        let ptr = unsafe { NonNull::new_unchecked(vec.as_mut_ptr().add(offset)) };
+        Self { ptr, len, vec: Some(vec), release: None }
+    }
+
+    /// Buffer of raw memory of `len` elements, released by `release(ptr, len)` when dropped.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must be valid for reads and writes of `len` elements, and aligned, until `release`
+    ///   is called.
+    pub unsafe fn from_raw_parts(
+        ptr: NonNull<T>,
+        len: usize,
+        release: impl FnOnce(NonNull<T>, usize) + Send + Sync + 'static,
+    ) -> Self {
+        let addr = ptr.as_ptr() as usize;
+        let release = move || release(unsafe { NonNull::new_unchecked(addr as *mut T) }, len);
+        Self { ptr, len, vec: None, release: Some(Box::new(release)) }
+    }
+
+    /// Add a callback when storage is released (after storage itself is released), e.g. for
+    /// accounting of allocators wrapping another allocator.
+    ///
+    /// The callback is also called when storage is moved out by [`TblisBuffer::into_vec`].
+    pub fn on_release(mut self, callback: impl FnOnce() + Send + Sync + 'static) -> Self {
+        self.release = Some(match self.release.take() {
+            Some(prev) => Box::new(move || {
+                prev();
+                callback();
+            }),
+            None => Box::new(callback),
+        });
+        self
+    }
+
+    /// Number of elements of storage.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Pointer to the first element of storage.
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    /// Mutable pointer to the first element of storage.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// Elements of storage.
+    ///
+    /// Storage from [`TblisAllocator::allocate`] is uninitialized; elements should be written
+    /// before reading.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Elements of storage.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Convert to vector and offset of the first element.
+    ///
+    /// Storage of vector is moved without copy; raw memory of custom allocators is copied to a new
+    /// vector, and released.
+    pub fn into_vec_offset(mut self) -> (Vec<T>, usize)
+    where
+        T: Clone,
+    {
+        match self.vec.take() {
+            Some(vec) => {
+                let offset = (self.ptr.as_ptr() as usize - vec.as_ptr() as usize) / core::mem::size_of::<T>().max(1);
+                (vec, offset)
+            },
+            None => (self.as_slice().to_vec(), 0),
+        }
+    }
+
+    /// Convert to vector of elements of storage.
+    ///
+    /// This function will move elements in memory if the vector is over-allocated for alignment.
+    pub fn into_vec(self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let (mut vec, offset) = self.into_vec_offset();
+        vec.drain(..offset);
+        vec
+    }
+}
+
+impl<T> Drop for TblisBuffer<T> {
+    fn drop(&mut self) {
+        drop(self.vec.take());
+        if let Some(release) = self.release.take() {
+            release();
+        }
+    }
+}
+
+/// Allocator of storage for einsum intermediates and outputs.
+///
+/// Storage is returned as owned [`TblisBuffer`], which gives memory back to the allocator when
+/// dropped, so that arena, huge page or NUMA-aware allocators can be implemented (by
+/// [`TblisBuffer::from_raw_parts`]). Allocators wrapping another allocator may count usage by
+/// [`TblisBuffer::on_release`].
+///
+/// Allocators are passed to einsum by [`TblisEinsumCfg`](crate::einsum_impl::TblisEinsumCfg)
+/// (field `allocator`), or to
+/// [`TblisWorkspace::with_allocator`](crate::workspace::TblisWorkspace::with_allocator).
+///
+/// # Example
+///
+/// A counting allocator that reports current and peak usage:
+///
+/// ```rust
+/// extern crate tblis_src;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+/// use tblis::prelude::*;
+///
+/// #[derive(Debug, Default)]
+/// struct CountingAllocator {
+///     current: Arc<AtomicUsize>,
+///     peak: AtomicUsize,
+/// }
+///
+/// impl TblisAllocator<f64> for CountingAllocator {
+///     unsafe fn allocate(&self, size: usize) -> Result<TblisBuffer<f64>, TblisError> {
+///         let current = self.current.fetch_add(size, Ordering::Relaxed) + size;
+///         self.peak.fetch_max(current, Ordering::Relaxed);
+///         let buffer = unsafe { TblisDefaultAllocator.allocate(size)? };
+///         let current = self.current.clone();
+///         Ok(buffer.on_release(move || _ = current.fetch_sub(size, Ordering::Relaxed)))
+///     }
+/// }
+///
+/// let allocator = Arc::new(CountingAllocator::default());
+/// let vec_a: Vec<f64> = (0..100).map(|x| x as f64).collect();
+/// let tsr_a = TblisTensor::new(vec_a.as_ptr() as *mut f64, &[10, 10], &[10, 1]);
+/// let cfg = TblisEinsumCfgBuilder::default().allocator(allocator.clone()).build().unwrap();
+/// let operands = [&tsr_a, &tsr_a, &tsr_a];
+/// let arr = unsafe { tblis_einsum("ij,jk,kl->il", &operands, "optimal", None, true, None, Some(cfg)) };
+/// // intermediate (10 x 10) and output (10 x 10)
+/// assert_eq!(allocator.peak.load(Ordering::Relaxed), 200);
+/// // output is released to allocator when dropped
+/// drop(arr);
+/// assert_eq!(allocator.current.load(Ordering::Relaxed), 0);
+/// ```
+pub trait TblisAllocator<T>: Debug + Send + Sync {
+    /// Allocate uninitialized storage of (at least) `size` elements.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that the storage is properly initialized before reading it.
+    unsafe fn allocate(&self, size: usize) -> Result<TblisBuffer<T>, TblisError>;
+}
+
+/// Default allocator: 64-byte aligned for no less than 128 elements (see
+/// [`aligned_uninitialized_vec_offset`]).
+///
+/// # Environment variables
+///
+/// Huge pages ([`TblisHugePageAllocator`]) can be enabled for the default allocator, without
+/// changing code, by environment variables (read once, at the first allocation):
+///
+/// - `TBLIS_RS_HUGEPAGE`: `on` to use huge pages for buffers no less than 32 MiB, or threshold in
+///   bytes (e.g. `1048576`); `off` (or unset) to disable.
+/// - `TBLIS_RS_FIRST_TOUCH`: `on` to initialize huge page buffers in parallel (see field
+///   `first_touch` of [`TblisHugePageAllocator`]).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TblisDefaultAllocator;
+
+/// Huge page allocator of [`TblisDefaultAllocator`], configured by environment variables once.
+static ENV_HUGE_PAGE_ALLOCATOR: OnceLock<Option<TblisHugePageAllocator>> = OnceLock::new();
+
+impl<T> TblisAllocator<T> for TblisDefaultAllocator {
+    unsafe fn allocate(&self, size: usize) -> Result<TblisBuffer<T>, TblisError> {
+        match ENV_HUGE_PAGE_ALLOCATOR.get_or_init(huge_page_allocator_from_env) {
+            Some(allocator) => unsafe { allocator.allocate(size) },
+            None => unsafe { default_buffer(size) },
+        }
+    }
+}
+
+/// Storage of the global allocator, 64-byte aligned for no less than 128 elements.
+unsafe fn default_buffer<T>(size: usize) -> Result<TblisBuffer<T>, TblisError> {
+    let (vec, offset) = unsafe { aligned_uninitialized_vec_offset::<T, 128>(size, 64)? };
+    Ok(TblisBuffer::from_vec(vec, offset))
+}
+
+/// Allocator using Linux transparent huge pages for large buffers.
+///
+/// Buffers no less than `threshold` bytes are mapped by `mmap` (anonymous, aligned to 2 MiB) and
+/// advised by `madvise(MADV_HUGEPAGE)`, reducing TLB misses for large intermediates; they are
+/// unmapped when dropped (see [`TblisBuffer`]). If `madvise` fails (e.g. kernel without
+/// transparent huge page support), the mapping is kept and backed by normal pages. Smaller buffers,
+/// and all buffers on other platforms, are allocated the same to [`TblisDefaultAllocator`].
+///
+/// Arrays of huge page storage are copied when converted to [`Vec`] (e.g.
+/// [`TblisArray::into_vec`](crate::containers::TblisArray::into_vec)).
+///
+/// # Fields
+///
+/// - `threshold`: minimum number of bytes to use huge pages (default: 32 MiB).
+/// - `first_touch`: whether to initialize (zero) huge page buffers in parallel after allocation, so
+///   that pages are placed on NUMA nodes of the threads that touch them (default: `false`).
+/// - `num_threads`: number of threads for first-touch initialization (default: `0`, which uses
+///   [`tblis_get_num_threads`](crate::threading::tblis_get_num_threads) at allocation, so that
+///   pages are touched by as many threads as TBLIS uses).
+///
+/// # Example
+///
+/// ```rust
+/// extern crate tblis_src;
+/// use std::sync::Arc;
+/// use tblis::prelude::*;
+///
+/// let allocator = TblisHugePageAllocatorBuilder::default().threshold(1 << 20).first_touch(true).build().unwrap();
+/// let cfg = TblisEinsumCfgBuilder::default().allocator(Arc::new(allocator)).build().unwrap();
+/// # let vec_a: Vec<f64> = (0..100).map(|x| x as f64).collect();
+/// # let tsr_a = TblisTensor::new(vec_a.as_ptr() as *mut f64, &[10, 10], &[10, 1]);
+/// let arr = unsafe { tblis_einsum("ij,jk,kl->il", &[&tsr_a, &tsr_a, &tsr_a], "optimal", None, true, None, Some(cfg)) };
+/// ```
+#[non_exhaustive]
+#[derive(Builder, Debug, Clone)]
+pub struct TblisHugePageAllocator {
+    #[builder(default = "32 << 20")]
+    pub threshold: usize,
+    #[builder(default = "false")]
+    pub first_touch: bool,
+    #[builder(default = "0")]
+    pub num_threads: usize,
+}
+
+impl Default for TblisHugePageAllocator {
+    fn default() -> Self {
+        TblisHugePageAllocatorBuilder::default().build().unwrap()
+    }
+}
+
+/// Size (and alignment) of huge pages.
+const HUGE_PAGE_SIZE: usize = 1 << 21;
+
+impl<T> TblisAllocator<T> for TblisHugePageAllocator {
+    unsafe fn allocate(&self, size: usize) -> Result<TblisBuffer<T>, TblisError> {
+        let numbytes = size.saturating_mul(core::mem::size_of::<T>());
+        if numbytes < self.threshold.max(1) {
+            return unsafe { default_buffer(size) };
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let buffer = unsafe { huge_page_buffer::<T>(size, numbytes)? };
+            if self.first_touch {
+                let num_threads = match self.num_threads {
+                    0 => crate::threading::tblis_get_num_threads(),
+                    n => n,
+                };
+                unsafe { first_touch(buffer.as_ptr() as *mut u8, numbytes, num_threads) };
+            }
+            Ok(buffer)
+        }
+        #[cfg(not(target_os = "linux"))]
+        unsafe {
+            default_buffer(size)
+        }
+    }
+}
+
+/// Anonymous mapping of `size` elements aligned to huge pages, advised by `MADV_HUGEPAGE`, and
+/// unmapped when dropped.
+#[cfg(target_os = "linux")]
+unsafe fn huge_page_buffer<T>(size: usize, numbytes: usize) -> Result<TblisBuffer<T>, TblisError> {
+    let maplen = numbytes.div_ceil(HUGE_PAGE_SIZE).checked_mul(HUGE_PAGE_SIZE);
+    let maplen = maplen.ok_or_else(|| TblisError::Allocation(format!("{numbytes} bytes overflow")))?;
+    // over-map by one huge page, and unmap the unaligned head and tail
+    let prot = libc::PROT_READ | libc::PROT_WRITE;
+    let flags = libc::MAP_PRIVATE | libc::MAP_ANONYMOUS;
+    let base = unsafe { libc::mmap(core::ptr::null_mut(), maplen + HUGE_PAGE_SIZE, prot, flags, -1, 0) };
+    if base == libc::MAP_FAILED {
+        let msg = format!("mmap of {} bytes: {}", maplen + HUGE_PAGE_SIZE, std::io::Error::last_os_error());
+        return Err(TblisError::Allocation(msg));
+    }
+    let head = (base as usize).next_multiple_of(HUGE_PAGE_SIZE) - base as usize;
+    let ptr = unsafe { (base as *mut u8).add(head) };
+    unsafe {
+        if head > 0 {
+            libc::munmap(base, head);
+        }
+        libc::munmap(ptr.add(maplen) as *mut libc::c_void, HUGE_PAGE_SIZE - head);
+        // failure leaves the mapping backed by normal pages, which is still valid storage
+        libc::madvise(ptr as *mut libc::c_void, maplen, libc::MADV_HUGEPAGE);
+    }
+    let ptr = unsafe { NonNull::new_unchecked(ptr as *mut T) };
+    let release = move |ptr: NonNull<T>, _| _ = unsafe { libc::munmap(ptr.as_ptr() as *mut libc::c_void, maplen) };
+    Ok(unsafe { TblisBuffer::from_raw_parts(ptr, size, release) })
+}
+
+/// Zero memory in parallel, each thread touching a contiguous range of huge pages.
+unsafe fn first_touch(ptr: *mut u8, numbytes: usize, num_threads: usize) {
+    let num_pages = numbytes.div_ceil(HUGE_PAGE_SIZE);
+    let pages_per_thread = num_pages.div_ceil(num_threads.max(1));
+    let addr = ptr as usize;
+    std::thread::scope(|s| {
+        for start in (0..num_pages).step_by(pages_per_thread.max(1)) {
+            let begin = start * HUGE_PAGE_SIZE;
+            let end = ((start + pages_per_thread) * HUGE_PAGE_SIZE).min(numbytes);
+            s.spawn(move || unsafe { core::ptr::write_bytes((addr + begin) as *mut u8, 0, end - begin) });
+        }
+    });
+}
+
+/// Huge page allocator configured by environment variables (see [`TblisDefaultAllocator`]).
+///
+/// Only called once, to initialize `ENV_HUGE_PAGE_ALLOCATOR`.
+fn huge_page_allocator_from_env() -> Option<TblisHugePageAllocator> {
+    let is_on =
+        |key: &str| std::env::var(key).is_ok_and(|val| matches!(val.to_lowercase().as_str(), "on" | "true" | "yes"));
+    let val = std::env::var("TBLIS_RS_HUGEPAGE").ok()?.to_lowercase();
+    let threshold = match val.as_str() {
+        "on" | "true" | "yes" => TblisHugePageAllocator::default().threshold,
+        _ => val.parse::<usize>().ok()?,
+    };
+    Some(TblisHugePageAllocator { threshold, first_touch: is_on("TBLIS_RS_FIRST_TOUCH"), num_threads: 0 })
+}
+
+/* #endregion */
+
+/* #region allocation functions */
 
 /// Create an uninitialized vector with the given size.
 ///
-/// This function depends on `aligned_alloc` feature.
-/// If `aligned_alloc` is enabled, it will align at 64-bit when size of vector
-/// elements is larger than 128.
+/// Memory of the returned vector is allocated by [`Vec`] itself, so it is only aligned to
+/// `align_of::<T>()`. For 64-byte aligned storage, use [`aligned_uninitialized_vec_offset`] (or
+/// [`TblisArray`](crate::containers::TblisArray) that handles this internally).
 ///
 /// # Safety
 ///
@@ -14,8 +396,8 @@ use core::ptr::NonNull;
 /// This is not a very good function, since `set_len` on uninitialized memory is
 /// undefined-behavior (UB).
 /// Nevertheless, if `T` is some type of `MaybeUninit`, then this will not UB.
-pub unsafe fn uninitialized_vec<T>(size: usize) -> Result<Vec<T>, String> {
-    unsafe { aligned_uninitialized_vec::<T, 128>(size, 64) }
+pub unsafe fn uninitialized_vec<T>(size: usize) -> Result<Vec<T>, TblisError> {
+    unsafe { unaligned_uninitialized_vec(size) }
 }
 
 /// Create an unaligned uninitialized vector with the given size.
@@ -28,9 +410,9 @@ pub unsafe fn uninitialized_vec<T>(size: usize) -> Result<Vec<T>, String> {
 /// undefined-behavior (UB).
 /// Nevertheless, if `T` is some type of `MaybeUninit`, then this will not UB.
 #[allow(clippy::uninit_vec)]
-pub unsafe fn unaligned_uninitialized_vec<T>(size: usize) -> Result<Vec<T>, String> {
+pub unsafe fn unaligned_uninitialized_vec<T>(size: usize) -> Result<Vec<T>, TblisError> {
     let mut v: Vec<T> = vec![];
-    v.try_reserve_exact(size).map_err(|e| format!("AllocationError: {:?}", e))?;
+    v.try_reserve_exact(size).map_err(|e| TblisError::Allocation(format!("{e:?}")))?;
     unsafe { v.set_len(size) };
     Ok(v)
 }
@@ -42,20 +424,39 @@ pub unsafe fn unaligned_uninitialized_vec<T>(size: usize) -> Result<Vec<T>, Stri
 /// - Ok(Some): pointer to the allocated memory.
 ///
 /// <https://users.rust-lang.org/t/how-can-i-allocate-aligned-memory-in-rust/33293>
-pub fn aligned_alloc(numbytes: usize, alignment: usize) -> Result<Option<NonNull<()>>, String> {
+pub fn aligned_alloc(numbytes: usize, alignment: usize) -> Result<Option<NonNull<()>>, TblisError> {
     if numbytes == 0 {
         return Ok(None);
     }
-    let layout =
-        alloc::alloc::Layout::from_size_align(numbytes, alignment).map_err(|e| format!("LayoutError: {:?}", e))?;
+    let layout = alloc::alloc::Layout::from_size_align(numbytes, alignment)
+        .map_err(|e| TblisError::Allocation(format!("{e:?}")))?;
     let pointer = NonNull::new(unsafe { alloc::alloc::alloc(layout) }).map(|p| p.cast::<()>());
     Ok(pointer)
 }
 
 /// Create an conditionally aligned uninitialized vector with the given size.
 ///
-/// - `N`: condition for alignment; if `N < size`, then this function will not allocate aligned
-///   vector.
+/// # Safety
+///
+/// Caller must ensure that the vector is properly initialized before using it.
+#[deprecated(
+    note = "memory from `aligned_alloc` can not be deallocated by `Vec`; this function no longer aligns, use `aligned_uninitialized_vec_offset` instead"
+)]
+pub unsafe fn aligned_uninitialized_vec<T, const N: usize>(
+    size: usize,
+    _alignment: usize,
+) -> Result<Vec<T>, TblisError> {
+    unsafe { unaligned_uninitialized_vec(size) }
+}
+
+/// Create an conditionally aligned uninitialized vector with the given size, by over-allocation.
+///
+/// Returns the vector and an offset (in elements), where `vec[offset..offset + size]` is the
+/// requested storage, and its first element is aligned to `alignment` bytes. The vector is
+/// allocated by [`Vec`] itself, so it is safe to be dropped or converted to other containers.
+///
+/// - `N`: condition for alignment; if `size < N`, then this function will not allocate aligned
+///   vector (offset is zero).
 ///
 /// # Safety
 ///
@@ -64,21 +465,20 @@ pub fn aligned_alloc(numbytes: usize, alignment: usize) -> Result<Option<NonNull
 /// This is not a very good function, since `set_len` on uninitialized memory is
 /// undefined-behavior (UB).
 /// Nevertheless, if `T` is some type of `MaybeUninit`, then this will not UB.
-#[allow(clippy::uninit_vec)]
-pub unsafe fn aligned_uninitialized_vec<T, const N: usize>(size: usize, alignment: usize) -> Result<Vec<T>, String> {
-    if size == 0 {
-        Ok(vec![])
-    } else if size < N {
-        unsafe { unaligned_uninitialized_vec(size) }
-    } else {
-        let sizeof = core::mem::size_of::<T>();
-        let pointer = aligned_alloc(size * sizeof, alignment)?;
-        if let Some(pointer) = pointer {
-            let mut v = unsafe { Vec::from_raw_parts(pointer.as_ptr() as *mut T, size, size) };
-            unsafe { v.set_len(size) };
-            Ok(v)
-        } else {
-            Err("Allocation failed (probably due to out-of-memory)".to_string())
-        }
+pub unsafe fn aligned_uninitialized_vec_offset<T, const N: usize>(
+    size: usize,
+    alignment: usize,
+) -> Result<(Vec<T>, usize), TblisError> {
+    let sizeof = core::mem::size_of::<T>().max(1);
+    if size < N || alignment <= core::mem::align_of::<T>() || alignment % sizeof != 0 {
+        return Ok((unsafe { unaligned_uninitialized_vec(size)? }, 0));
+    }
+    let v: Vec<T> = unsafe { unaligned_uninitialized_vec(size + alignment / sizeof)? };
+    let offset = v.as_ptr().align_offset(alignment);
+    if offset > alignment / sizeof {
+        return Err(TblisError::Allocation(format!("unable to align memory to {alignment} bytes")));
     }
+    Ok((v, offset))
 }
+
+/* #endregion */
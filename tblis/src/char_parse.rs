@@ -1,5 +1,6 @@
 //! Parse rust's char to c_char (with mapping).
 
+use crate::error::TblisError;
 use core::ffi::c_char;
 use std::collections::BTreeSet;
 
@@ -11,8 +12,8 @@ static MAP62: &[u8; 62] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ
 /// 1. If all indices are in ASCII range, directly cast to c_char.
 /// 2. If there are no more than 62 characters in all indices, map them to [a-zA-Z0-9].
 /// 3. If there are no more than 128 characters in all indices, map them to extended ASCII (0-127).
-/// 4. Otherwise will panic.
-pub fn char_parse(indices: &[&str]) -> Result<Vec<Vec<c_char>>, String> {
+/// 4. Otherwise will return [`TblisError::TooManyLabels`].
+pub fn char_parse(indices: &[&str]) -> Result<Vec<Vec<c_char>>, TblisError> {
     let all_chars = indices.iter().flat_map(|s| s.chars()).collect::<BTreeSet<char>>();
     let all_chars_len = all_chars.len();
     // rule 1
@@ -36,8 +37,6 @@ pub fn char_parse(indices: &[&str]) -> Result<Vec<Vec<c_char>>, String> {
                 .collect::<std::collections::BTreeMap<char, c_char>>();
             Ok(indices.iter().map(|s| s.chars().map(|c| char_map[&c]).collect()).collect())
         },
-        _ => Err(format!(
-            "Too many unique characters in indices ({all_chars_len} > 128). Your indices are: {indices:#?}"
-        )),
+        _ => Err(TblisError::TooManyLabels { count: all_chars_len, max: 128 }),
     }
 }
@@ -1,7 +1,11 @@
 //! TBLIS data containers.
 
+use crate::einsum_impl::shape_to_stride;
 use crate::prelude::*;
+use crate::views::{layout_extent, layout_self_overlapping, layouts_overlap};
 use core::ffi::c_int;
+use core::ops::Range;
+use num::{Complex, Float};
 
 /* #region TblisTensor */
 
@@ -76,15 +80,16 @@ where
     ///
     /// # Errors
     ///
-    /// If the tensor is not a scalar (i.e., its total size is not 1), an error string is returned.
-    pub fn to_scalar(&self) -> Result<T, String> {
+    /// If the tensor is not a scalar (i.e., its total size is not 1), [`TblisError::NotScalar`] is
+    /// returned.
+    pub fn to_scalar(&self) -> Result<T, TblisError> {
         // only size=1 tensors can be converted to scalars
         if self.shape.iter().product::<isize>() == 1 {
             let val = unsafe { *self.data * self.scalar };
             let val = if self.conj { val.conj() } else { val };
             Ok(val)
         } else {
-            Err("Tensor is not a scalar".into())
+            Err(TblisError::NotScalar { shape: self.shape.clone() })
         }
     }
 
@@ -99,6 +104,350 @@ where
         self.conj = conj;
         self
     }
+
+    /// Memory extent (range of addresses in bytes) covered by the layout of tensor.
+    ///
+    /// Returns `None` if the tensor contains no element. If the extent overflows (invalid layout),
+    /// the whole address space is returned.
+    pub fn memory_extent(&self) -> Option<Range<usize>> {
+        let full = 0..usize::MAX;
+        let Ok(extent) = layout_extent(&self.shape, &self.stride) else { return Some(full) };
+        let (min, max) = extent?;
+        let size = core::mem::size_of::<T>() as isize;
+        let addr = self.data as usize;
+        let start = min.checked_mul(size).and_then(|offset| addr.checked_add_signed(offset));
+        let end =
+            max.checked_add(1).and_then(|n| n.checked_mul(size)).and_then(|offset| addr.checked_add_signed(offset));
+        match (start, end) {
+            (Some(start), Some(end)) => Some(start..end),
+            _ => Some(full),
+        }
+    }
+
+    /// Whether two tensors share some memory location.
+    ///
+    /// Only provable overlap is reported: tensors interleaved in the same memory region without
+    /// common elements (such as even and odd columns of a matrix, or real and imaginary parts of a
+    /// complex tensor) are not overlapping. For layouts too large to be decided within bounded
+    /// search, `false` is returned.
+    pub fn overlaps<U>(&self, other: &TblisTensor<U>) -> bool
+    where
+        U: TblisFloatAPI,
+    {
+        let a = (self.data as usize, self.shape.as_slice(), self.stride.as_slice(), core::mem::size_of::<T>());
+        let b = (other.data as usize, other.shape.as_slice(), other.stride.as_slice(), core::mem::size_of::<U>());
+        layouts_overlap(a, b) == Some(true)
+    }
+
+    /// Whether different indices of tensor may refer to the same memory location (for example,
+    /// zero stride of dimension larger than one).
+    ///
+    /// This check is conservative: some exotic interleaved layouts that are not overlapping may
+    /// also be considered as self-overlapping.
+    pub fn is_self_overlapping(&self) -> bool {
+        layout_self_overlapping(&self.shape, &self.stride)
+    }
+}
+
+impl<R> TblisTensor<Complex<R>>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    /// Real view of complex tensor, with additional trailing axis (length 2, stride 1) of real
+    /// and imaginary parts.
+    ///
+    /// Strides of other axes are doubled. Data is not copied, so real-only operations (such as
+    /// [`tblis_tensor_add`], [`tblis_tensor_mult`]) can be performed on the parts of complex
+    /// tensor.
+    ///
+    /// Note that `conj` and `scalar` are not representable in real view; the returned tensor has
+    /// `conj = false` and `scalar = 1`.
+    pub fn as_real_parts(&self) -> TblisTensor<R> {
+        let mut shape = self.shape.clone();
+        let mut stride = self.stride.iter().map(|&s| 2 * s).collect::<Vec<isize>>();
+        shape.push(2);
+        stride.push(1);
+        TblisTensor::new(self.data as *mut R, &shape, &stride)
+    }
+
+    /// Real view of the real part of complex tensor (no copy; `conj` and `scalar` are not kept).
+    pub fn re(&self) -> TblisTensor<R> {
+        let stride = self.stride.iter().map(|&s| 2 * s).collect::<Vec<isize>>();
+        TblisTensor::new(self.data as *mut R, &self.shape, &stride)
+    }
+
+    /// Real view of the imaginary part of complex tensor (no copy; `conj` and `scalar` are not
+    /// kept).
+    pub fn im(&self) -> TblisTensor<R> {
+        let stride = self.stride.iter().map(|&s| 2 * s).collect::<Vec<isize>>();
+        TblisTensor::new(unsafe { (self.data as *mut R).add(1) }, &self.shape, &stride)
+    }
+}
+
+impl<R> TblisTensor<R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    /// Complex view of interleaved real tensor; reverse of [`TblisTensor::as_real_parts`].
+    ///
+    /// # Panics
+    ///
+    /// - If layout can not be viewed as complex. Use [`TblisTensor::as_complex_f`] for fallible
+    ///   version.
+    pub fn as_complex(&self) -> TblisTensor<Complex<R>> {
+        self.as_complex_f().unwrap()
+    }
+
+    /// Complex view of interleaved real tensor; reverse of [`TblisTensor::as_real_parts`].
+    ///
+    /// The last axis must be of length 2 and stride 1 (real and imaginary parts), and strides of
+    /// other axes must be even. The trailing axis is removed and other strides are halved. The
+    /// returned tensor has `conj = false`, and `scalar` is the real scalar of this tensor.
+    ///
+    /// # Errors
+    ///
+    /// [`TblisError::InvalidLayout`] if the layout does not satisfy the above requirements.
+    pub fn as_complex_f(&self) -> Result<TblisTensor<Complex<R>>, TblisError> {
+        let ndim = self.shape.len();
+        let valid = ndim > 0
+            && self.shape[ndim - 1] == 2
+            && self.stride[ndim - 1] == 1
+            && self.stride[..ndim - 1].iter().all(|&s| s % 2 == 0);
+        if !valid {
+            return Err(TblisError::InvalidLayout { shape: self.shape.clone(), stride: self.stride.clone(), len: 0 });
+        }
+        let shape = &self.shape[..ndim - 1];
+        let stride = self.stride[..ndim - 1].iter().map(|&s| s / 2).collect::<Vec<isize>>();
+        let mut tsr = TblisTensor::new(self.data as *mut Complex<R>, shape, &stride);
+        tsr.scalar = Complex::new(self.scalar, R::zero());
+        Ok(tsr)
+    }
+}
+
+/* #endregion */
+
+/* #region TblisArray */
+
+/// Owned contiguous tensor for TBLIS operations.
+///
+/// Storage is a [`TblisBuffer<T>`] from an allocator (by default [`TblisDefaultAllocator`]), and is
+/// given back to that allocator when the array is dropped. For large tensors, the default storage
+/// is over-allocated so that the first element is aligned to 64 bytes.
+///
+/// Layout of this struct is always contiguous, either row-major (C-style) or col-major
+/// (Fortran-style).
+///
+/// # Notes
+///
+/// - Use [`TblisArray::view`] / [`TblisArray::view_mut`] for safe operations.
+/// - Use [`ToTblisTensor::to_tblis_tensor`] for raw [`TblisTensor`] as input, or
+///   [`TblisArray::to_tblis_tensor_mut`] as output; the array must outlive it.
+/// - With crate feature `ndarray`, this struct can be converted to [`ndarray::ArrayD`] without
+///   copying data if storage is a vector of the global allocator
+///   ([`ArrayFromTblisTensor::into_array`]).
+#[derive(Debug)]
+pub struct TblisArray<T>
+where
+    T: TblisFloatAPI,
+{
+    data: TblisBuffer<T>,
+    shape: Vec<isize>,
+    stride: Vec<isize>,
+    row_major: bool,
+}
+
+impl<T> TblisArray<T>
+where
+    T: TblisFloatAPI,
+{
+    /// Create an uninitialized (64-byte aligned) array.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that the array is properly initialized before reading it.
+    pub unsafe fn uninit(shape: &[isize], row_major: bool) -> Self {
+        unsafe { Self::uninit_f(shape, row_major).unwrap() }
+    }
+
+    /// Create an uninitialized (64-byte aligned) array.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that the array is properly initialized before reading it.
+    pub unsafe fn uninit_f(shape: &[isize], row_major: bool) -> Result<Self, TblisError> {
+        unsafe { Self::uninit_in_f(shape, row_major, &TblisDefaultAllocator) }
+    }
+
+    /// Create an uninitialized array, with storage from allocator.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that the array is properly initialized before reading it.
+    pub unsafe fn uninit_in_f(
+        shape: &[isize],
+        row_major: bool,
+        allocator: &(impl TblisAllocator<T> + ?Sized),
+    ) -> Result<Self, TblisError> {
+        let size = layout_size(shape)?;
+        let data = unsafe { allocator.allocate(size)? };
+        if data.len() < size {
+            let msg = format!("allocator gives storage of length {} for {size} elements", data.len());
+            return Err(TblisError::Allocation(msg));
+        }
+        #[cfg(feature = "debug_checks")]
+        let data = {
+            let mut data = data;
+            crate::debug_checks::poison(data.as_mut_slice());
+            data
+        };
+        let stride = shape_to_stride(shape, row_major);
+        Ok(Self { data, shape: shape.to_vec(), stride, row_major })
+    }
+
+    /// Create a zero-initialized (64-byte aligned) array.
+    pub fn zeros(shape: &[isize], row_major: bool) -> Self {
+        let mut arr = unsafe { Self::uninit(shape, row_major) };
+        arr.as_mut_slice().fill(T::zero());
+        arr
+    }
+
+    /// Create an array from vector (no copy) with contiguous layout.
+    ///
+    /// # Panics
+    ///
+    /// - If length of vector does not match the size of shape. Use [`TblisArray::from_vec_f`] for
+    ///   fallible version.
+    pub fn from_vec(data: Vec<T>, shape: &[isize], row_major: bool) -> Self {
+        Self::from_vec_f(data, shape, row_major).unwrap()
+    }
+
+    /// Create an array from vector (no copy) with contiguous layout.
+    pub fn from_vec_f(data: Vec<T>, shape: &[isize], row_major: bool) -> Result<Self, TblisError> {
+        let size = layout_size(shape)?;
+        if data.len() != size {
+            let (shape, stride) = (shape.to_vec(), shape_to_stride(shape, row_major));
+            return Err(TblisError::InvalidLayout { shape, stride, len: data.len() });
+        }
+        let stride = shape_to_stride(shape, row_major);
+        Ok(Self { data: TblisBuffer::from_vec(data, 0), shape: shape.to_vec(), stride, row_major })
+    }
+
+    pub fn shape(&self) -> &[isize] {
+        &self.shape
+    }
+
+    pub fn stride(&self) -> &[isize] {
+        &self.stride
+    }
+
+    pub fn is_row_major(&self) -> bool {
+        self.row_major
+    }
+
+    /// Number of elements.
+    pub fn size(&self) -> usize {
+        self.shape.iter().product::<isize>() as usize
+    }
+
+    /// Elements in memory order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data.as_slice()[..self.size()]
+    }
+
+    /// Elements in memory order.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let size = self.size();
+        &mut self.data.as_mut_slice()[..size]
+    }
+
+    /// Borrow-checked immutable view of this array.
+    pub fn view(&self) -> TblisView<'_, T> {
+        TblisView::new(self.as_slice(), &self.shape, &self.stride)
+    }
+
+    /// Borrow-checked mutable view of this array.
+    pub fn view_mut(&mut self) -> TblisViewMut<'_, T> {
+        let (shape, stride) = (self.shape.clone(), self.stride.clone());
+        TblisViewMut::new(self.as_mut_slice(), &shape, &stride)
+    }
+
+    /// Convert to TBLIS tensor that is to be written; the array must outlive it.
+    ///
+    /// Different from [`ToTblisTensor::to_tblis_tensor`], the data pointer is derived from a
+    /// mutable borrow, so writing through it is sound.
+    pub fn to_tblis_tensor_mut(&mut self) -> TblisTensor<T> {
+        let (shape, stride) = (self.shape.clone(), self.stride.clone());
+        TblisTensor::new(self.as_mut_slice().as_mut_ptr(), &shape, &stride)
+    }
+
+    /// Convert to vector of elements in memory order.
+    ///
+    /// This function will move elements in memory if the storage is over-allocated for alignment,
+    /// and copy elements if the storage is not a vector (see [`TblisBuffer::into_vec`]).
+    pub fn into_vec(self) -> Vec<T> {
+        let size = self.size();
+        let mut data = self.data.into_vec();
+        data.truncate(size);
+        data
+    }
+
+    /// (dev-only) Decompose to raw parts: storage, shape, stride.
+    pub fn into_raw_parts(self) -> (TblisBuffer<T>, Vec<isize>, Vec<isize>) {
+        (self.data, self.shape, self.stride)
+    }
+
+    /// (dev-only) Number of elements that the storage can hold.
+    pub(crate) fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// (dev-only) Reuse storage for another shape; elements are left as is (uninitialized).
+    ///
+    /// Storage must have sufficient capacity (see [`TblisArray::capacity`]).
+    pub(crate) fn reshape_uninit(&mut self, shape: &[isize], row_major: bool) {
+        debug_assert!(shape.iter().product::<isize>() as usize <= self.capacity());
+        self.stride = shape_to_stride(shape, row_major);
+        self.shape = shape.to_vec();
+        self.row_major = row_major;
+        #[cfg(feature = "debug_checks")]
+        crate::debug_checks::poison(self.data.as_mut_slice());
+    }
+}
+
+impl<T> Clone for TblisArray<T>
+where
+    T: TblisFloatAPI,
+{
+    /// Copy elements to a new (64-byte aligned) array of the same layout.
+    ///
+    /// Storage is from [`TblisDefaultAllocator`], regardless of the allocator of this array.
+    fn clone(&self) -> Self {
+        let mut arr = unsafe { Self::uninit(&self.shape, self.row_major) };
+        arr.as_mut_slice().copy_from_slice(self.as_slice());
+        arr
+    }
+}
+
+impl<T> ToTblisTensor<T> for TblisArray<T>
+where
+    T: TblisFloatAPI,
+{
+    fn to_tblis_tensor(&self) -> TblisTensor<T> {
+        TblisTensor::new(self.as_slice().as_ptr() as *mut T, &self.shape, &self.stride)
+    }
+}
+
+/// Number of elements of shape.
+fn layout_size(shape: &[isize]) -> Result<usize, TblisError> {
+    if shape.iter().any(|&s| s < 0) {
+        let stride = vec![0; shape.len()];
+        return Err(TblisError::InvalidLayout { shape: shape.to_vec(), stride, len: 0 });
+    }
+    let size = shape.iter().try_fold(1_usize, |acc, &s| acc.checked_mul(s as usize));
+    let size = size.filter(|&size| size <= isize::MAX as usize);
+    size.ok_or_else(|| TblisError::InvalidLayout { shape: shape.to_vec(), stride: vec![0; shape.len()], len: 0 })
 }
 
 /* #endregion */
@@ -0,0 +1,77 @@
+//! Debug checks for uninitialized buffers (crate feature `debug_checks`).
+//!
+//! Intermediates and internally allocated outputs are uninitialized, which is correct only if every
+//! element is overwritten before read. With this feature:
+//! - every fresh buffer ([`TblisArray::uninit_f`], [`TblisArray::uninit_in_f`], and buffers reused
+//!   from [`TblisWorkspace`]) is filled with signalling NaN;
+//! - outputs of einsum steps are verified to be finite, if all inputs (operands, scalars, and
+//!   output when $\beta \neq 0$) are finite; otherwise [`TblisError::NonFinite`] is returned.
+//!
+//! These checks are slow, and only meant for debugging.
+
+use crate::prelude::*;
+
+/// Fill storage with signalling NaN (of both real and imaginary parts for complex types).
+pub(crate) fn poison<T>(data: &mut [T])
+where
+    T: TblisFloatAPI,
+{
+    let nbytes = core::mem::size_of_val(data);
+    let ptr = data.as_mut_ptr();
+    match core::mem::size_of::<T::Real>() {
+        4 => unsafe { core::slice::from_raw_parts_mut(ptr as *mut u32, nbytes / 4).fill(0x7fa0_0000) },
+        8 => unsafe { core::slice::from_raw_parts_mut(ptr as *mut u64, nbytes / 8).fill(0x7ff4_0000_0000_0000) },
+        _ => unreachable!("TBLIS real types are f32 or f64"),
+    }
+}
+
+/// Whether all elements of tensor are finite (field `scalar` is not applied).
+pub(crate) fn tensor_finite<T>(tsr: &TblisTensor<T>) -> bool
+where
+    T: TblisFloatAPI,
+{
+    if tsr.shape.contains(&0) {
+        return true;
+    }
+    let ndim = tsr.shape.len();
+    let mut index = vec![0isize; ndim];
+    loop {
+        let offset: isize = index.iter().zip(&tsr.stride).map(|(&i, &s)| i * s).sum();
+        if !unsafe { *tsr.data.offset(offset) }.is_finite() {
+            return false;
+        }
+        // next multi-index (last index runs fastest)
+        let mut k = ndim;
+        loop {
+            if k == 0 {
+                return true;
+            }
+            k -= 1;
+            index[k] += 1;
+            if index[k] < tsr.shape[k] {
+                break;
+            }
+            index[k] = 0;
+        }
+    }
+}
+
+/// Whether all inputs of einsum are finite; output is only read if `beta` is not zero.
+pub(crate) fn inputs_finite<T>(operands: &[&TblisTensor<T>], alpha: T, beta: T, out: Option<&TblisTensor<T>>) -> bool
+where
+    T: TblisFloatAPI,
+{
+    let out_finite = beta == T::zero() || out.is_none_or(tensor_finite);
+    alpha.is_finite() && beta.is_finite() && out_finite && operands.iter().all(|&tsr| tensor_finite(tsr))
+}
+
+/// Check that all elements of output tensor are finite.
+pub(crate) fn check_finite_f<T>(tsr: &TblisTensor<T>, context: impl FnOnce() -> String) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    match tensor_finite(tsr) {
+        true => Ok(()),
+        false => Err(TblisError::NonFinite(context())),
+    }
+}
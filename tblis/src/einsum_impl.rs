@@ -3,9 +3,80 @@
 //! For crate `ndarray`, also implements einsum execution.
 
 use crate::prelude::*;
-use opt_einsum_path::typing::{ContractionType, SizeLimitType, TensorShapeType};
+use core::fmt::{Display, Formatter};
+use derive_builder::Builder;
+use opt_einsum_path::contract::PathInfo;
+use opt_einsum_path::parser::parse_einsum_input;
+use opt_einsum_path::typing::{
+    ArrayIndexType, ContractionType, PathType, SizeDictType, SizeLimitType, SizeType, TensorShapeType,
+};
 use opt_einsum_path::{contract_path, PathOptimizer};
-use std::collections::BTreeSet;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Configuration for einsum operations $C = \alpha \, \mathrm{einsum}(\cdots) + \beta C$.
+///
+/// # Fields
+///
+/// - `alpha`: The scalar multiplier $\alpha$ for einsum result (default: `1`).
+/// - `beta`: The scalar multiplier $\beta$ for output tensor $C$ (default: `0`). This is only
+///   effective when pre-allocated output tensor is provided; otherwise output is allocated
+///   internally and $\beta$ is treated as zero.
+/// - `allocator`: Allocator for intermediates and internally allocated output (default: `None`,
+///   which uses [`TblisDefaultAllocator`], or the allocator of workspace in
+///   [`TblisEinsumPlan::execute_with_workspace`]).
+/// - `memory_budget`: Budget (in bytes) of memory allocated in execution (default: `None`, no
+///   budget). Peak memory of intermediates and output is computed before execution starts (see
+///   [`TblisEinsumPlan::peak_bytes`]); execution fails with [`TblisError::MemoryBudget`] if it
+///   exceeds the budget. Unlike `memory_limit` of [`tblis_einsum`], which only guides the path
+///   optimizer, this is a hard limit.
+/// - `check_overlap`: Whether to check that pre-allocated output tensor is not self-overlapping and
+///   does not overlap with operands (default: `true`); see also [`TblisAddCfg`].
+///
+/// # Usage
+///
+/// This struct is used as builder. For example, to accumulate result into pre-allocated output
+/// (`alpha=0.5`, `beta=1.0`):
+///
+/// ```rust
+/// # use tblis::prelude::*;
+/// let cfg = TblisEinsumCfgBuilder::default()
+///     .alpha(0.5)
+///     .beta(1.0)
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// # See also
+///
+/// - [`tblis_einsum`] for usage.
+/// - [`TblisMultCfg`] for configuration of pairwise tensor multiplication.
+#[non_exhaustive]
+#[derive(Builder, Debug, Clone)]
+pub struct TblisEinsumCfg<T>
+where
+    T: TblisFloatAPI,
+{
+    #[builder(default = "T::one()")]
+    pub alpha: T,
+    #[builder(default = "T::zero()")]
+    pub beta: T,
+    #[builder(default, setter(strip_option))]
+    pub allocator: Option<Arc<dyn TblisAllocator<T>>>,
+    #[builder(default, setter(strip_option))]
+    pub memory_budget: Option<usize>,
+    #[builder(default = "true")]
+    pub check_overlap: bool,
+}
+
+impl<T> Default for TblisEinsumCfg<T>
+where
+    T: TblisFloatAPI,
+{
+    fn default() -> Self {
+        TblisEinsumCfgBuilder::default().build().unwrap()
+    }
+}
 
 /// (dev-only) Intermediate representation of einsum contraction step.
 ///
@@ -24,13 +95,124 @@ pub struct TblisContractStep {
     pub shape_c: Vec<isize>,
 }
 
+/// (dev-only) Expand ellipsis (`"..."`) in einsum subscripts, and evaluate broadcasted shapes.
+///
+/// # Returns
+///
+/// - Expanded subscripts with explicit output, e.g. `"...ij,...jk"` with shapes `[[1, 2, 3], [4, 3,
+///   5]]` gives `"cij,cjk->cik"` (labels of ellipsis dimensions are chosen from unused characters).
+/// - Shapes of operands after broadcasting, e.g. `[[4, 2, 3], [4, 3, 5]]` for the case above.
+///
+/// Same to numpy, only dimensions represented by ellipsis can be broadcasted, and only size-1
+/// dimensions can be broadcasted. Broadcasted dimensions should be treated as stride 0 in
+/// execution.
+pub fn tblis_einsum_expand_f(
+    subscripts: &str,
+    operands: &[TensorShapeType],
+) -> Result<(String, Vec<TensorShapeType>), TblisError> {
+    let (input_subscripts, output_subscript, _) =
+        parse_einsum_input(subscripts, operands).map_err(TblisError::PathOptimization)?;
+    let input_list: Vec<&str> = input_subscripts.split(',').collect();
+    let mut size_dict: BTreeMap<char, usize> = BTreeMap::new();
+    for (subscript, shape) in input_list.iter().zip(operands) {
+        if subscript.chars().count() != shape.len() {
+            let (subscript, shape) = (subscript.to_string(), shape.iter().map(|&s| s as isize).collect());
+            return Err(TblisError::SubscriptLength { subscript, shape });
+        }
+        for (c, &s) in subscript.chars().zip(shape) {
+            let ellipsis_label = !subscripts.contains(c);
+            match size_dict.get(&c) {
+                Some(&existing) if existing == s => (),
+                Some(&existing) if ellipsis_label && (existing == 1 || s == 1) => {
+                    size_dict.insert(c, existing.max(s));
+                },
+                Some(&existing) => {
+                    let (expected, found, subscript) = (existing as isize, s as isize, subscript.to_string());
+                    return Err(TblisError::ShapeMismatch { index: c, expected, found, subscript });
+                },
+                None => {
+                    size_dict.insert(c, s);
+                },
+            }
+        }
+    }
+    let shapes = input_list.iter().map(|subscript| subscript.chars().map(|c| size_dict[&c]).collect()).collect();
+    Ok((format!("{input_subscripts}->{output_subscript}"), shapes))
+}
+
+/// Explicit (user-supplied) contraction path, bypassing path optimization.
+///
+/// This can be passed as `optimize` parameter to [`tblis_einsum`], [`TblisEinsumPlan::new`] or
+/// [`tblis_einsum_path`]. Each step lists positions of the tensors (in the current list of
+/// tensors, same convention to `numpy.einsum_path`) to be contracted; the result is appended to
+/// the end of the list.
+///
+/// Compared to passing `Vec<Vec<usize>>` directly (which is also accepted by
+/// [`opt_einsum_path`]), this type additionally validates that each step is non-empty without
+/// duplicated positions. Steps involving more than two tensors are decomposed to pairwise
+/// contractions (see [`tblis_einsum_prep_f`]).
+///
+/// # Example
+///
+/// ```rust
+/// use tblis::prelude::*;
+///
+/// let shapes = [vec![10, 20], vec![20, 30], vec![30, 40]];
+/// let path = TblisExplicitPath(vec![vec![1, 2], vec![0, 1]]);
+/// let report = tblis_einsum_path("ij,jk,kl->il", &shapes, path, None);
+/// assert_eq!(report.path, vec![vec![1, 2], vec![0, 1]]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TblisExplicitPath(pub PathType);
+
+impl PathOptimizer for TblisExplicitPath {
+    fn optimize_path(
+        &mut self,
+        inputs: &[&ArrayIndexType],
+        _output: &ArrayIndexType,
+        _size_dict: &SizeDictType,
+        _memory_limit: Option<SizeType>,
+    ) -> Result<PathType, String> {
+        tblis_einsum_check_path_f(&self.0, inputs.len()).map_err(|err| err.to_string())?;
+        Ok(self.0.clone())
+    }
+}
+
+/// Validate explicit contraction path for `num_operands` tensors.
+///
+/// Each step must contract distinct tensors in bounds, and the path must reduce all tensors to a
+/// single output.
+pub fn tblis_einsum_check_path_f(path: &[Vec<usize>], num_operands: usize) -> Result<(), TblisError> {
+    let err = |msg: String| TblisError::InvalidPath { path: path.to_vec(), msg };
+    let mut n = num_operands;
+    for (i, step) in path.iter().enumerate() {
+        if step.is_empty() {
+            return Err(err(format!("step {i} is empty")));
+        }
+        if let Some((k, &pos)) = step.iter().enumerate().find(|&(k, pos)| step[..k].contains(pos)) {
+            return Err(err(format!("step {i} has duplicated position {pos} (at {k})")));
+        }
+        if let Some(&pos) = step.iter().find(|&&pos| pos >= n) {
+            return Err(err(format!("step {i} has position {pos} out of bounds for {n} tensors")));
+        }
+        n -= step.len() - 1;
+    }
+    if n != 1 {
+        return Err(err(format!("path does not reduce to single output, ended with {n} tensors")));
+    }
+    Ok(())
+}
+
 /// (dev-only) Prepare einsum contraction steps for TBLIS internally from output of
 /// [opt_einsum_path::contract_path].
 ///
+/// TBLIS only supports trace/scale/set of one tensor, and add/mult for two tensors. A single step
+/// involving more than two tensors (e.g. from path optimization in strict memory limit) is
+/// decomposed into pairwise steps, with greedy order of smallest intermediate first.
+///
 /// # Panics
 ///
-/// A single step in contraction involves more than two tensors. This is not supported in TBLIS
-/// (TBLIS only supports trace/scale/set of one tensor, and add/mult for two tensors).
+/// - If path optimization failed.
 ///
 /// # See also
 ///
@@ -55,14 +237,22 @@ pub fn tblis_einsum_prep_f(
     operands: &[TensorShapeType],
     optimize: impl PathOptimizer,
     memory_limit: impl Into<SizeLimitType>,
-) -> Result<Vec<TblisContractStep>, String> {
-    let (_, path_info) = contract_path(subscripts, operands, optimize, memory_limit)?;
-    let size_dict = path_info.size_dict;
+) -> Result<Vec<TblisContractStep>, TblisError> {
+    let (_, path_info) =
+        contract_path(subscripts, operands, optimize, memory_limit).map_err(TblisError::PathOptimization)?;
+    contract_steps_from_info(&path_info)
+}
+
+/// Convert contraction list of [`PathInfo`] to TBLIS contraction steps.
+fn contract_steps_from_info(path_info: &PathInfo) -> Result<Vec<TblisContractStep>, TblisError> {
+    let size_dict = &path_info.size_dict;
     let mut steps = Vec::new();
-    for contract_step in path_info.contraction_list {
-        let ContractionType { indices, einsum_str, .. } = contract_step;
-        // Only pairwise / single tensor contractions are supported.
-        match indices.len() {
+    // number of tensors in the current list
+    let mut num_tensors = path_info.input_subscripts.split(',').count();
+    for contract_step in &path_info.contraction_list {
+        let ContractionType { indices, einsum_str, .. } = contract_step.clone();
+        let num_indices = indices.len();
+        match num_indices {
             2 => {
                 let einsum_str_split: Vec<&str> = einsum_str.split("->").collect();
                 let idx_c = einsum_str_split[1].to_string();
@@ -79,14 +269,193 @@ pub fn tblis_einsum_prep_f(
                 let shape_c = idx_c.chars().map(|c| size_dict[&c] as isize).collect();
                 steps.push(TblisContractStep { indices, idx_a, idx_b: None, idx_c, shape_c });
             },
-            _ => return Err("Only pairwise / single tensor contractions are supported.".to_string()),
+            0 => {
+                let msg = format!("Contraction step involves no tensor, got {einsum_str:?}.");
+                return Err(TblisError::UnsupportedContraction(msg));
+            },
+            _ => steps.extend(decompose_step(&indices, &einsum_str, num_tensors, size_dict)),
         }
+        num_tensors -= num_indices - 1;
     }
     Ok(steps)
 }
 
+/// Decompose contraction step involving more than two tensors into pairwise steps.
+///
+/// - `indices`: positions of tensors involved in this step (in the current list of tensors).
+/// - `einsum_str`: einsum subscripts of this step, e.g. `"ab,bc,cd->ad"`.
+/// - `num_tensors`: number of tensors in the current list.
+///
+/// Pairs are chosen greedily by the smallest intermediate. Intermediates are appended to the end
+/// of tensor list, which is the same convention to contraction path.
+fn decompose_step(
+    indices: &[usize],
+    einsum_str: &str,
+    num_tensors: usize,
+    size_dict: &SizeDictType,
+) -> Vec<TblisContractStep> {
+    let (inputs, output) = einsum_str.split_once("->").unwrap();
+    // (position in current list, subscript) of remaining tensors in this step
+    let mut remaining: Vec<(usize, String)> =
+        indices.iter().zip(inputs.split(',')).map(|(&i, s)| (i, s.to_string())).collect();
+    let mut num_tensors = num_tensors;
+    let mut steps = Vec::new();
+    while remaining.len() > 1 {
+        let is_last = remaining.len() == 2;
+        // subscript of intermediate of contracting `remaining[i]` and `remaining[j]`
+        let idx_inter = |i: usize, j: usize| -> String {
+            if is_last {
+                return output.to_string();
+            }
+            let mut idx = String::new();
+            for c in remaining[i].1.chars().chain(remaining[j].1.chars()) {
+                let needed = output.contains(c)
+                    || remaining.iter().enumerate().any(|(k, (_, s))| k != i && k != j && s.contains(c));
+                if needed && !idx.contains(c) {
+                    idx.push(c);
+                }
+            }
+            idx
+        };
+        let size = |idx: &str| idx.chars().map(|c| size_dict[&c] as f64).product::<f64>();
+        let (i, j) = (0..remaining.len())
+            .flat_map(|i| (i + 1..remaining.len()).map(move |j| (i, j)))
+            .min_by(|&(i1, j1), &(i2, j2)| size(&idx_inter(i1, j1)).total_cmp(&size(&idx_inter(i2, j2))))
+            .unwrap();
+        let idx_c = idx_inter(i, j);
+        let shape_c = idx_c.chars().map(|c| size_dict[&c] as isize).collect();
+        let (pos_a, idx_a) = remaining[i].clone();
+        let (pos_b, idx_b) = remaining[j].clone();
+        steps.push(TblisContractStep {
+            indices: vec![pos_a, pos_b],
+            idx_a,
+            idx_b: Some(idx_b),
+            idx_c: idx_c.clone(),
+            shape_c,
+        });
+        // update positions after removing the pair and appending the intermediate
+        remaining.remove(j);
+        remaining.remove(i);
+        for (pos, _) in remaining.iter_mut() {
+            *pos -= (*pos > pos_a) as usize + (*pos > pos_b) as usize;
+        }
+        num_tensors -= 1;
+        remaining.push((num_tensors - 1, idx_c));
+    }
+    steps
+}
+
+/// Contraction path report of einsum (equivalent to `numpy.einsum_path`).
+///
+/// # Fields
+///
+/// - `path`: contraction path executed by TBLIS (list of operand positions contracted at each
+///   step), the same to indices of `steps`.
+/// - `steps`: contraction steps for TBLIS (see [`TblisContractStep`]).
+/// - `info`: full report from [`opt_einsum_path::contract_path`]; [`Display`] of this struct prints
+///   its human-readable table.
+///
+/// # Notes
+///
+/// Path optimizers (or explicit paths) may contract more than two operands in one step; TBLIS
+/// contracts them pairwise, so such a step is decomposed into several steps. `path` and `steps`
+/// describe the decomposed (executed) contraction and are authoritative; `info` (also its table,
+/// and [`TblisEinsumPath::opt_cost`], [`TblisEinsumPath::speedup`] and
+/// [`TblisEinsumPath::largest_intermediate`]) describes the path given by the optimizer
+/// (`info.path`), before decomposition.
+#[derive(Debug, Clone)]
+pub struct TblisEinsumPath {
+    pub path: PathType,
+    pub steps: Vec<TblisContractStep>,
+    pub info: PathInfo,
+}
+
+impl TblisEinsumPath {
+    /// FLOP count of naive (single-step) contraction.
+    pub fn naive_cost(&self) -> SizeType {
+        self.info.naive_cost
+    }
+
+    /// FLOP count of optimized contraction path.
+    pub fn opt_cost(&self) -> SizeType {
+        self.info.opt_cost
+    }
+
+    /// Theoretical speedup (`naive_cost / opt_cost`).
+    pub fn speedup(&self) -> f64 {
+        self.info.speedup
+    }
+
+    /// Number of elements of the largest intermediate tensor.
+    pub fn largest_intermediate(&self) -> SizeType {
+        self.info.largest_intermediate
+    }
+}
+
+impl Display for TblisEinsumPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.info)
+    }
+}
+
+/// Evaluate contraction path of einsum, without performing contraction.
+///
+/// This is the equivalent of `numpy.einsum_path`. Ellipsis in subscripts is supported (expanded
+/// by [`tblis_einsum_expand_f`]).
+///
+/// # Parameters
+///
+/// - `subscripts`: einsum subscripts, e.g. `"ij,jk->ik"`.
+/// - `shapes`: shapes of input tensors.
+/// - `optimize`: contraction path optimization strategy (see [`opt_einsum_path::contract_path`]).
+/// - `memory_limit`: memory limit for contraction path optimization (see
+///   [`opt_einsum_path::contract_path`]).
+///
+/// # Example
+///
+/// ```rust
+/// use tblis::prelude::*;
+///
+/// let path = tblis_einsum_path("ij,jk,kl->il", &[vec![10, 20], vec![20, 30], vec![30, 40]], "optimal", None);
+/// assert_eq!(path.steps.len(), 2);
+/// assert!(path.speedup() >= 1.0);
+/// println!("{path}");
+/// ```
+///
+/// # Panics
+///
+/// - This function will panic if failed. Use [`tblis_einsum_path_f`] for fallible version.
+pub fn tblis_einsum_path(
+    subscripts: &str,
+    shapes: &[TensorShapeType],
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+) -> TblisEinsumPath {
+    tblis_einsum_path_f(subscripts, shapes, optimize, memory_limit).unwrap()
+}
+
+/// Evaluate contraction path of einsum, without performing contraction.
+///
+/// # See also
+///
+/// [`tblis_einsum_path`] for non-fallible version.
+pub fn tblis_einsum_path_f(
+    subscripts: &str,
+    shapes: &[TensorShapeType],
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+) -> Result<TblisEinsumPath, TblisError> {
+    let (subscripts, shapes) = tblis_einsum_expand_f(subscripts, shapes)?;
+    let (_, info) =
+        contract_path(&subscripts, &shapes, optimize, memory_limit).map_err(TblisError::PathOptimization)?;
+    let steps = contract_steps_from_info(&info)?;
+    // path of decomposed steps, instead of the optimizer path that may contract more than two operands
+    let path = steps.iter().map(|step| step.indices.clone()).collect();
+    Ok(TblisEinsumPath { path, steps, info })
+}
+
 /// Generate strides by shape and row-major/col-major flag.
-fn shape_to_stride(shape: &[isize], row_major: bool) -> Vec<isize> {
+pub(crate) fn shape_to_stride(shape: &[isize], row_major: bool) -> Vec<isize> {
     let ndim = shape.len();
     let mut stride = vec![1isize; ndim];
     if ndim == 0 {
@@ -106,78 +475,52 @@ fn shape_to_stride(shape: &[isize], row_major: bool) -> Vec<isize> {
     stride
 }
 
-/// Perform trace operation on a tensor. This can be used when [`tblis_tensor_mult`] could not
-/// handle the case where `idx_a` or `idx_b` contains redundant indices.
-fn tblis_trace_f<T>(
-    subscript_prev: &str,
-    subscript_traced: &str,
-    tsr_prev: &TblisTensor<T>,
-    row_major: bool,
-) -> Result<(Vec<T>, TblisTensor<T>), String>
-where
-    T: TblisFloatAPI,
-{
-    let idx_prev: Vec<char> = subscript_prev.chars().collect();
-    let idx_traced: Vec<char> = subscript_traced.chars().collect();
-    let subscript_traced = idx_traced.iter().collect::<String>();
-    if idx_prev.len() != tsr_prev.shape.len() {
-        return Err("Subscript length does not match tensor ndim.".to_string());
-    }
-    // build shape/stride for traced tensor
-    let shape_traced = idx_traced
-        .iter()
-        .map(|&c| tsr_prev.shape[idx_prev.iter().position(|&x| x == c).unwrap()])
-        .collect::<Vec<isize>>();
-    let stride_traced = shape_to_stride(&shape_traced, row_major);
-    let size_traced = shape_traced.iter().product::<isize>() as usize;
-    let vec_traced = unsafe { crate::alloc_vec::uninitialized_vec::<T>(size_traced)? };
-    let mut tsr_traced = TblisTensor::new(vec_traced.as_ptr() as *mut T, &shape_traced, &stride_traced);
-    let cfg = TblisAddCfgBuilder::default().beta(T::zero()).build().unwrap();
-    unsafe { tblis_tensor_add_f(tsr_prev, subscript_prev, &mut tsr_traced, &subscript_traced, Some(cfg))? };
-    Ok((vec_traced, tsr_traced))
-}
-
 /// Perform einsum operation using TBLIS.
 ///
 /// # Parameters
 ///
-/// - `subscripts`: einsum subscripts, e.g. `"ij,jk->ik"`.
+/// - `subscripts`: einsum subscripts, e.g. `"ij,jk->ik"`. Numpy-style ellipsis is supported, e.g.
+///   `"...ij,...jk->...ik"`, where size-1 dimensions represented by ellipsis are broadcasted.
 /// - `operands`: list of input tensors (see [`TblisTensor`] for data structure and
-///   [`ToTblisTensor`] for conversion trait).
+///   [`ToTblisTensor`] for conversion trait). Fields `conj` and `scalar` of each operand are
+///   honored: the operand is treated as `scalar * conj(data)` (conjugation only if `conj` is set).
 /// - `optimize`: contraction path optimization strategy (see [`opt_einsum_path::contract_path`]).
-/// - `memory_limit`: memory limit for contraction path optimization (see
-///   [`opt_einsum_path::contract_path`]).
+///   Explicit contraction path can be given by [`TblisExplicitPath`].
+/// - `memory_limit`: memory limit (number of elements) for contraction path optimization (see
+///   [`opt_einsum_path::contract_path`]). An explicit numeric limit is also enforced as hard
+///   ceiling of intermediates by index slicing (see [`TblisEinsumPlan::with_memory_ceiling`]);
+///   `None` and `MaxInput` do not perform slicing.
 /// - `row_major`: whether the input/output tensors are in row-major (C-style) or col-major
 ///   (Fortran-style).
 /// - `out_tblis_tensor`: pre-allocated output tensor. If `None`, the output tensor is allocated
 ///   internally.
+/// - `cfg`: Optional configuration for the operation (see [`TblisEinsumCfg`]).
+///   - `alpha`: The scalar multiplier $\alpha$ for einsum result (default: `1`).
+///   - `beta`: The scalar multiplier $\beta$ for pre-allocated output tensor (default: `0`).
 ///
 /// # Returns
 ///
-/// - `Option<(Vec<T>, TblisTensor<T>)>`: If the output tensor is allocated internally, returns the
-///   allocated vector and tensor.
+/// - `Option<TblisArray<T>>`: If the output tensor is allocated internally, returns the owned
+///   array.
 /// - If the output tensor is provided, returns `None`.
 ///
 /// If you activated cargo feature `ndarray`, you can convert the output to [ndarray::ArrayD] by
 ///
 /// ```rust
+/// # extern crate tblis_src;
 /// use tblis::prelude::*;
 /// # use ndarray::prelude::*;
-/// # let vec_g = vec![0.0f64; 2*2*2*2]; // pre-allocated data
-/// # let tsr_g = TblisTensor::new(vec_g.as_ptr() as *mut f64, &[2,2,2,2], &[4,2,1,1]);
+/// # let arr_g = TblisArray::<f64>::zeros(&[2, 2, 2, 2], true);
 /// // with predefined
-/// // - `vec_g`: `Vec<T>`
-/// // - `tsr_g`: `TblisTensor<T>`
-/// let out_g: ArrayD<_> = (vec_g, tsr_g).into_array(); // ndarray::ArrayD<T>
+/// // - `arr_g`: `TblisArray<T>`
+/// let out_g: ArrayD<_> = arr_g.into_array(); // ndarray::ArrayD<T> (no data copy)
 /// ```
 ///
 /// # Panics
 ///
 /// - This function will panic if failed. Use [`tblis_einsum_f`] for fallible version.
-/// - TBLIS cannot handle a single contraction step involving more than two tensors.
 /// - [opt_einsum_path::contract_path] may fail if the subscripts and tensor shapes that user
-///   provides are invalid, or give a contraction step involving more than two tensors in strict
-///   memory limit.
+///   provides are invalid.
 /// - This function allows non-ASCII characters in einsum subscripts, but TBLIS may panic if too
 ///   many characters are used. It is recommended to use no more than 52 characters in total. It is
 ///   not allowed to use more than 128 characters.
@@ -237,12 +580,13 @@ where
 ///             None,                     // memory limit (None means no limit, see crate opt-einsum-path)
 ///             true,                     // row-major (true) or col-major (false)
 ///             None,                     // pre-allocated output tensor (None to allocate internally)
+///             None,                     // einsum configuration (alpha, beta)
 ///         )
 ///     };
-///     let (vec_g, tsr_g) = out_g.unwrap(); // (underlying data, tensor shape/stride info)
+///     let arr_g = out_g.unwrap(); // owned array with underlying data and shape/stride info
 ///
 ///     // transform tblis object back to ndarray object
-///     let arr_g = (vec_g, tsr_g).into_array().into_dimensionality().unwrap();
+///     let arr_g = arr_g.into_array().into_dimensionality().unwrap();
 ///     arr_g
 /// }
 ///
@@ -257,6 +601,8 @@ where
 /// # See also
 ///
 /// - [`tblis_einsum_f`] for fallible version.
+/// - [`TblisEinsumPlan`] for reusable plan, if the same contraction is performed many times; with
+///   [`TblisWorkspace`], intermediate buffers are also reused.
 /// - [`opt_einsum_path::contract_path`] for details of contraction path optimization.
 pub unsafe fn tblis_einsum<T>(
     subscripts: &str,
@@ -265,11 +611,12 @@ pub unsafe fn tblis_einsum<T>(
     memory_limit: impl Into<SizeLimitType>,
     row_major: bool,
     out_tblis_tensor: Option<&mut TblisTensor<T>>,
-) -> Option<(Vec<T>, TblisTensor<T>)>
+    cfg: Option<TblisEinsumCfg<T>>,
+) -> Option<TblisArray<T>>
 where
     T: TblisFloatAPI,
 {
-    unsafe { tblis_einsum_f(subscripts, operands, optimize, memory_limit, row_major, out_tblis_tensor).unwrap() }
+    unsafe { tblis_einsum_f(subscripts, operands, optimize, memory_limit, row_major, out_tblis_tensor, cfg).unwrap() }
 }
 
 /// Perform einsum operation using TBLIS.
@@ -281,7 +628,6 @@ where
 /// # See also
 ///
 /// - [`tblis_einsum`] for non-fallible version.
-#[allow(clippy::type_complexity)]
 pub unsafe fn tblis_einsum_f<T>(
     subscripts: &str,
     operands: &[&TblisTensor<T>],
@@ -289,118 +635,12 @@ pub unsafe fn tblis_einsum_f<T>(
     memory_limit: impl Into<SizeLimitType>,
     row_major: bool,
     out_tblis_tensor: Option<&mut TblisTensor<T>>,
-) -> Result<Option<(Vec<T>, TblisTensor<T>)>, String>
+    cfg: Option<TblisEinsumCfg<T>>,
+) -> Result<Option<TblisArray<T>>, TblisError>
 where
     T: TblisFloatAPI,
 {
     let shapes: Vec<Vec<usize>> = operands.iter().map(|t| t.shape.iter().map(|&s| s as usize).collect()).collect();
-    let steps = tblis_einsum_prep_f(subscripts, &shapes, optimize, memory_limit)?;
-    let mut tensor_list: Vec<(TblisTensor<T>, Option<Vec<T>>)> = operands.iter().map(|&t| (t.clone(), None)).collect();
-    let num_steps = steps.len();
-    for (idx_step, step) in steps.iter().enumerate() {
-        let TblisContractStep { indices, idx_a, idx_b, idx_c, shape_c } = step;
-
-        if let Some(idx_b) = idx_b {
-            // case of tensor mult
-            let mut idx_a = idx_a.clone();
-            let mut idx_b = idx_b.clone();
-
-            // handle trace here
-            // - tblis_tensor_mult is not able to handle combined contract with trace
-            // - trace means something like "eca, ab -> e", where 'c' in first and 'b' in second are redundant
-            //   indices, should be traced before contraction
-            let idx_a_set = idx_a.chars().collect::<BTreeSet<char>>();
-            let idx_b_set = idx_b.chars().collect::<BTreeSet<char>>();
-            let idx_c_set = idx_c.chars().collect::<BTreeSet<char>>();
-            let idx_a_only = &(&idx_a_set - &idx_b_set) - &idx_c_set;
-            let idx_b_only = &(&idx_b_set - &idx_a_set) - &idx_c_set;
-            if !idx_a_only.is_empty() {
-                let tsr_a = &tensor_list[indices[0]].0;
-                let idx_a_traced = idx_a.chars().filter(|c| !idx_a_only.contains(c)).collect::<String>();
-                let (vec_a_traced, tsr_a_traced) = tblis_trace_f(&idx_a, &idx_a_traced, tsr_a, row_major)?;
-                tensor_list[indices[0]] = (tsr_a_traced, Some(vec_a_traced));
-                idx_a = idx_a_traced;
-            }
-            if !idx_b_only.is_empty() {
-                let tsr_b = &tensor_list[indices[1]].0;
-                let idx_b_traced = idx_b.chars().filter(|c| !idx_b_only.contains(c)).collect::<String>();
-                let (vec_b_traced, tsr_b_traced) = tblis_trace_f(&idx_b, &idx_b_traced, tsr_b, row_major)?;
-                tensor_list[indices[1]] = (tsr_b_traced, Some(vec_b_traced));
-                idx_b = idx_b_traced;
-            }
-
-            let tsr_a = &tensor_list[indices[0]].0;
-            let tsr_b = &tensor_list[indices[1]].0;
-            let is_last_step = idx_step == num_steps - 1;
-            let (vec_c, mut tsr_c) = if is_last_step {
-                if let Some(ref out_tblis_tensor) = out_tblis_tensor {
-                    // final tensor with pre-allocated space
-                    let tsr_c = out_tblis_tensor;
-                    if tsr_c.shape != *shape_c {
-                        return Err("Output tensor shape mismatch.".to_string());
-                    }
-                    (None, (*tsr_c).clone())
-                } else {
-                    // intermediate tensor or final tensor without pre-allocated space
-                    let size_c = shape_c.iter().product::<isize>() as usize;
-                    let vec_c = unsafe { crate::alloc_vec::uninitialized_vec::<T>(size_c)? };
-                    let stride_c = shape_to_stride(shape_c, row_major);
-                    let tsr_c = TblisTensor::new(vec_c.as_ptr() as *mut T, shape_c, &stride_c);
-                    (Some(vec_c), tsr_c)
-                }
-            } else {
-                // intermediate tensor or final tensor without pre-allocated space
-                let size_c = shape_c.iter().product::<isize>() as usize;
-                let vec_c = unsafe { crate::alloc_vec::uninitialized_vec::<T>(size_c)? };
-                let stride_c = shape_to_stride(shape_c, row_major);
-                let tsr_c = TblisTensor::new(vec_c.as_ptr() as *mut T, shape_c, &stride_c);
-                (Some(vec_c), tsr_c)
-            };
-            // handle empty idx_a/idx_b (scalar-like operations)
-            match (idx_a.is_empty(), idx_b.is_empty()) {
-                (false, false) => unsafe {
-                    tblis_tensor_mult_f(tsr_a, &idx_a, tsr_b, &idx_b, &mut tsr_c, idx_c, None)?;
-                },
-                (true, true) => {
-                    let val_a = tsr_a.clone().set_scalar(T::one()).to_scalar()?;
-                    let val_b = tsr_b.clone().set_scalar(T::one()).to_scalar()?;
-                    unsafe { *tsr_c.data = val_a * val_b };
-                },
-                (true, false) => {
-                    let val_a = tsr_a.clone().set_scalar(T::one()).to_scalar()?;
-                    let add_cfg = TblisAddCfgBuilder::default().alpha(val_a).beta(T::zero()).build().unwrap();
-                    unsafe { tblis_tensor_add_f(tsr_b, &idx_b, &mut tsr_c, idx_c, Some(add_cfg))? };
-                },
-                (false, true) => {
-                    let val_b = tsr_b.clone().set_scalar(T::one()).to_scalar()?;
-                    let add_cfg = TblisAddCfgBuilder::default().alpha(val_b).beta(T::zero()).build().unwrap();
-                    unsafe { tblis_tensor_add_f(tsr_a, &idx_a, &mut tsr_c, idx_c, Some(add_cfg))? };
-                },
-            };
-            tensor_list.push((tsr_c, vec_c));
-        } else {
-            // case of tensor transpose (implement by add)
-            let tsr_a = &tensor_list[indices[0]].0;
-            let size_c = shape_c.iter().product::<isize>() as usize;
-            let vec_c = unsafe { crate::alloc_vec::uninitialized_vec::<T>(size_c)? };
-            let stride_c = shape_to_stride(shape_c, row_major);
-            let mut tsr_c = TblisTensor::new(vec_c.as_ptr() as *mut T, shape_c, &stride_c);
-            let cfg = TblisAddCfgBuilder::default().beta(T::zero()).build().unwrap();
-            unsafe { tblis_tensor_add_f(tsr_a, idx_a, &mut tsr_c, idx_c, Some(cfg))? };
-            tensor_list.push((tsr_c, Some(vec_c)));
-        }
-        // remove used tensors
-        let mut indices = indices.to_vec();
-        indices.sort_unstable_by(|a, b| b.cmp(a));
-        for i in indices {
-            tensor_list.remove(i);
-        }
-    }
-    assert!(tensor_list.len() == 1);
-    let (tsr, vec_opt) = tensor_list.pop().unwrap();
-    if let Some(vec) = vec_opt {
-        Ok(Some((vec, tsr)))
-    } else {
-        Err("Final tensor does not own its data.".to_string())
-    }
+    let plan = TblisEinsumPlan::new_f(subscripts, &shapes, optimize, memory_limit, row_major)?;
+    unsafe { plan.execute_f(operands, out_tblis_tensor, cfg) }
 }
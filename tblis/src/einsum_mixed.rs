@@ -0,0 +1,384 @@
+//! Einsum with mixed real and complex operands.
+
+#![allow(clippy::too_many_arguments)]
+
+use crate::prelude::*;
+use crate::split_complex::TblisSplitAllocator;
+use crate::tensor_ops::check_overlap_f;
+use num::{Complex, Float, Zero};
+use opt_einsum_path::typing::SizeLimitType;
+use opt_einsum_path::PathOptimizer;
+use std::sync::Arc;
+
+/* #region containers */
+
+/// Operand of [`tblis_einsum_mixed`], either real or complex (of the same precision).
+#[derive(Debug, Clone, Copy)]
+pub enum TblisMixedTensor<'a, R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    Real(&'a TblisTensor<R>),
+    Complex(&'a TblisTensor<Complex<R>>),
+}
+
+impl<'a, R> From<&'a TblisTensor<R>> for TblisMixedTensor<'a, R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    fn from(tsr: &'a TblisTensor<R>) -> Self {
+        Self::Real(tsr)
+    }
+}
+
+impl<'a, R> From<&'a TblisTensor<Complex<R>>> for TblisMixedTensor<'a, R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    fn from(tsr: &'a TblisTensor<Complex<R>>) -> Self {
+        Self::Complex(tsr)
+    }
+}
+
+impl<R> TblisMixedTensor<'_, R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    pub fn shape(&self) -> &[isize] {
+        match self {
+            Self::Real(tsr) => &tsr.shape,
+            Self::Complex(tsr) => &tsr.shape,
+        }
+    }
+
+    pub fn is_complex(&self) -> bool {
+        matches!(self, Self::Complex(_))
+    }
+}
+
+/// Result of [`tblis_einsum_mixed`]; complex if any operand is complex.
+#[derive(Debug, Clone)]
+pub enum TblisMixedArray<R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    Real(TblisArray<R>),
+    Complex(TblisArray<Complex<R>>),
+}
+
+impl<R> TblisMixedArray<R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    pub fn shape(&self) -> &[isize] {
+        match self {
+            Self::Real(arr) => arr.shape(),
+            Self::Complex(arr) => arr.shape(),
+        }
+    }
+
+    pub fn is_complex(&self) -> bool {
+        matches!(self, Self::Complex(_))
+    }
+
+    /// Real array, or `None` if the result is complex.
+    pub fn into_real(self) -> Option<TblisArray<R>> {
+        match self {
+            Self::Real(arr) => Some(arr),
+            Self::Complex(_) => None,
+        }
+    }
+
+    /// Complex array; real result is promoted (copied) to complex.
+    pub fn into_complex(self) -> TblisArray<Complex<R>> {
+        match self {
+            Self::Real(arr) => {
+                let (shape, row_major) = (arr.shape().to_vec(), arr.is_row_major());
+                let data = arr.as_slice().iter().map(|&x| Complex::new(x, R::zero())).collect();
+                TblisArray::from_vec(data, &shape, row_major)
+            },
+            Self::Complex(arr) => arr,
+        }
+    }
+}
+
+/* #endregion */
+
+/* #region einsum */
+
+/// Perform einsum operation with mixed real and complex operands using TBLIS.
+///
+/// The result is real if all operands are real and $\alpha$ is real, otherwise complex.
+///
+/// # Parameters
+///
+/// - `subscripts`: einsum subscripts, e.g. `"ij,jk->ik"` (same to [`tblis_einsum`]).
+/// - `operands`: list of input tensors, real or complex (see [`TblisMixedTensor`]). Fields `conj`
+///   and `scalar` of each operand are honored.
+/// - `optimize`: contraction path optimization strategy (see [`opt_einsum_path::contract_path`]).
+/// - `memory_limit`: memory limit for contraction path optimization (see [`tblis_einsum`]); this
+///   applies to the real contraction described in notes below.
+/// - `row_major`: whether the output tensor is in row-major (C-style) or col-major (Fortran-style).
+/// - `out`: pre-allocated (complex) output tensor. If `None`, the output tensor is allocated
+///   internally.
+/// - `cfg`: Optional configuration for the operation (see [`TblisEinsumCfg`]), with complex `alpha`
+///   and `beta`. Field `allocator` allocates the output and real intermediates (as complex
+///   elements, two real elements each); `memory_budget` also counts the real intermediates.
+///
+/// # Returns
+///
+/// - `Option<TblisMixedArray<R>>`: If the output tensor is allocated internally, returns the owned
+///   array.
+/// - If the output tensor is provided, returns `None`.
+///
+/// # Notes
+///
+/// Complex operands are viewed as real tensors with an additional trailing axis of real and
+/// imaginary parts (stride 1, see [`TblisTensor::as_real_parts`]), and the whole einsum is
+/// performed as one real contraction, so real operands are never copied:
+///
+/// - If exactly one operand is complex, the real contraction is written directly to the real view
+///   of the complex output.
+/// - If more than one operand is complex, products of their real and imaginary parts are kept as
+///   separate axes of the real result (of size $2^k$ times the output for $k$ complex operands),
+///   which are then added to the real and imaginary parts of the output. This is efficient for a
+///   few complex operands; for many complex operands, consider [`tblis_einsum`] on complex tensors
+///   instead.
+///
+/// # Panics
+///
+/// - This function will panic if failed. Use [`tblis_einsum_mixed_f`] for fallible version.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate tblis_src;
+/// use num::Complex;
+/// use tblis::prelude::*;
+///
+/// let mut vec_h: Vec<f64> = (0..9).map(|x| x as f64).collect();
+/// let mut vec_c: Vec<Complex<f64>> = (0..3).map(|x| Complex::new(x as f64, 1.0)).collect();
+/// let tsr_h = TblisTensor::new(vec_h.as_mut_ptr(), &[3, 3], &[3, 1]);
+/// let tsr_c = TblisTensor::new(vec_c.as_mut_ptr(), &[3], &[1]);
+///
+/// let operands = [(&tsr_h).into(), (&tsr_c).into()];
+/// let arr = unsafe { tblis_einsum_mixed("ij,j->i", &operands, "optimal", None, true, None, None).unwrap() };
+/// assert!(arr.is_complex());
+/// assert_eq!(arr.shape(), &[3]);
+/// ```
+///
+/// # See also
+///
+/// - [`tblis_einsum_mixed_f`] for fallible version.
+/// - [`tblis_einsum`] for operands of the same type.
+pub unsafe fn tblis_einsum_mixed<R>(
+    subscripts: &str,
+    operands: &[TblisMixedTensor<R>],
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+    row_major: bool,
+    out: Option<&mut TblisTensor<Complex<R>>>,
+    cfg: Option<TblisEinsumCfg<Complex<R>>>,
+) -> Option<TblisMixedArray<R>>
+where
+    R: TblisFloatAPI + Float + Send + Sync + 'static,
+    Complex<R>: TblisFloatAPI,
+{
+    unsafe { tblis_einsum_mixed_f(subscripts, operands, optimize, memory_limit, row_major, out, cfg).unwrap() }
+}
+
+/// Perform einsum operation with mixed real and complex operands using TBLIS.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_einsum_mixed`] for non-fallible version.
+pub unsafe fn tblis_einsum_mixed_f<R>(
+    subscripts: &str,
+    operands: &[TblisMixedTensor<R>],
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+    row_major: bool,
+    out: Option<&mut TblisTensor<Complex<R>>>,
+    cfg: Option<TblisEinsumCfg<Complex<R>>>,
+) -> Result<Option<TblisMixedArray<R>>, TblisError>
+where
+    R: TblisFloatAPI + Float + Send + Sync + 'static,
+    Complex<R>: TblisFloatAPI,
+{
+    let (input_list, output) = explicit_subscripts(subscripts);
+    if input_list.len() != operands.len() {
+        return Err(TblisError::OperandCount { expected: input_list.len(), found: operands.len() });
+    }
+    let TblisEinsumCfg { alpha, beta, allocator, memory_budget, check_overlap, .. } = cfg.unwrap_or_default();
+    let allocator: Arc<dyn TblisAllocator<Complex<R>>> = allocator.unwrap_or_else(|| Arc::new(TblisDefaultAllocator));
+    let allocator_real: Arc<dyn TblisAllocator<R>> = Arc::new(TblisSplitAllocator(allocator.clone()));
+    let num_complex = operands.iter().filter(|t| t.is_complex()).count();
+    // re/im labels are not used by subscripts, also after ellipsis expansion; ASCII letters first
+    let shapes = operands.iter().map(|t| t.shape().iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let (subscripts_expanded, _) = tblis_einsum_expand_f(subscripts, &shapes)?;
+    let mut labels = ('a'..='z')
+        .chain('A'..='Z')
+        .chain(('\u{80}'..=char::MAX).filter(|c| c.is_alphabetic()))
+        .filter(|&c| !subscripts_expanded.contains(c));
+
+    // real views of operands; each complex one has additional trailing re/im axis
+    let mut conj_list = Vec::with_capacity(num_complex);
+    let mut scalar = alpha;
+    let mut subscripts_real = Vec::with_capacity(operands.len());
+    let mut tensors_real = Vec::with_capacity(operands.len());
+    let mut labels_out = String::new();
+    for (&subscript, operand) in input_list.iter().zip(operands) {
+        match operand {
+            TblisMixedTensor::Real(tsr) => {
+                subscripts_real.push(subscript.to_string());
+                tensors_real.push((*tsr).clone());
+            },
+            TblisMixedTensor::Complex(tsr) => {
+                let label = labels.next().unwrap();
+                conj_list.push(tsr.conj);
+                scalar = scalar * tsr.scalar;
+                subscripts_real.push(format!("{subscript}{label}"));
+                tensors_real.push(tsr.as_real_parts());
+                labels_out.push(label);
+            },
+        }
+    }
+    let subscripts_real = format!("{}->{output}{labels_out}", subscripts_real.join(","));
+    let shapes = tensors_real.iter().map(|t| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let plan = TblisEinsumPlan::<R>::new_f(&subscripts_real, &shapes, optimize, memory_limit, row_major)?;
+    let tensors_real = tensors_real.iter().collect::<Vec<_>>();
+    let shape_out = &plan.shape_out[..plan.shape_out.len() - num_complex];
+
+    // all real: plain real einsum
+    let (one, zero) = (Complex::new(R::one(), R::zero()), Complex::zero());
+    if num_complex == 0 && out.is_none() && scalar.im.is_zero() {
+        let cfg = TblisEinsumCfg {
+            alpha: scalar.re,
+            beta: R::zero(),
+            allocator: Some(allocator_real),
+            memory_budget,
+            check_overlap,
+        };
+        let arr = unsafe { plan.execute_f(&tensors_real, None, Some(cfg))? };
+        return Ok(arr.map(TblisMixedArray::Real));
+    }
+
+    if let Some(out) = &out {
+        if out.shape != shape_out {
+            return Err(TblisError::OutputMismatch { expected: shape_out.to_vec(), found: out.shape.clone() });
+        }
+        if check_overlap {
+            check_overlap_f(out, &[])?;
+            let overlaps = |t: &TblisMixedTensor<R>| match t {
+                TblisMixedTensor::Real(tsr) => out.overlaps(*tsr),
+                TblisMixedTensor::Complex(tsr) => out.overlaps(*tsr),
+            };
+            if let Some(operand) = operands.iter().position(overlaps) {
+                return Err(TblisError::Aliasing { operand });
+            }
+        }
+    }
+    // one complex operand with real factors can be written directly to real view of output;
+    // otherwise the real result is allocated, and combined to output afterwards
+    let conj = conj_list.iter().any(|&c| c);
+    let direct = num_complex == 1 && beta.im.is_zero() && ((scalar.im.is_zero() && !conj) || beta.is_zero());
+    if let Some(budget) = memory_budget {
+        let held =
+            if out.is_none() { shape_out.iter().product::<isize>() as usize * size_of::<Complex<R>>() } else { 0 };
+        plan.check_memory_budget_held_f(budget, !direct, held)?;
+    }
+    // output allocated internally is uninitialized, beta is not applicable
+    let (mut arr_out, mut tsr_out, beta) = match out {
+        Some(out) => (None, out.clone(), beta),
+        None => {
+            let mut arr = unsafe { TblisArray::<Complex<R>>::uninit_in_f(shape_out, row_major, &*allocator)? };
+            let tsr = arr.to_tblis_tensor_mut();
+            (Some(arr), tsr, zero)
+        },
+    };
+    let idx_out = (0..shape_out.len()).map(|i| char::from_u32('a' as u32 + i as u32).unwrap()).collect::<String>();
+    let cfg_real =
+        |alpha, beta| TblisEinsumCfg { alpha, beta, allocator: None, memory_budget: None, check_overlap: false };
+
+    if direct {
+        let mut tsr_out_real = tsr_out.as_real_parts();
+        let (alpha_real, beta_real) =
+            if scalar.im.is_zero() && !conj { (scalar.re, beta.re) } else { (R::one(), R::zero()) };
+        let cfg = TblisEinsumCfg { allocator: Some(allocator_real), ..cfg_real(alpha_real, beta_real) };
+        unsafe { plan.execute_f(&tensors_real, Some(&mut tsr_out_real), Some(cfg))? };
+        // conjugation and complex scalar are applied to the result, since all other operands are
+        // real (here beta is zero)
+        if conj || scalar.im != R::zero() {
+            let cfg = TblisScaleCfgBuilder::default().alpha(scalar).conj(conj).check_overlap(false).build().unwrap();
+            unsafe { tblis_tensor_scale_f(&mut tsr_out, &idx_out, Some(cfg))? };
+        }
+        return Ok(arr_out.take().map(TblisMixedArray::Complex));
+    }
+
+    // real result, with trailing axes of real/imaginary parts of each complex operand
+    let cfg = TblisEinsumCfg { allocator: Some(allocator_real.clone()), ..cfg_real(R::one(), R::zero()) };
+    let arr_real = unsafe { plan.execute_f(&tensors_real, None, Some(cfg))?.unwrap() };
+    if beta.is_zero() {
+        let cfg = TblisSetCfgBuilder::default().check_overlap(false).build().unwrap();
+        unsafe { tblis_tensor_set_f(&mut tsr_out, &idx_out, zero, Some(cfg))? };
+    } else if beta != one {
+        let cfg = TblisScaleCfgBuilder::default().alpha(beta).check_overlap(false).build().unwrap();
+        unsafe { tblis_tensor_scale_f(&mut tsr_out, &idx_out, Some(cfg))? };
+    }
+    let ndim = shape_out.len();
+    let tsr_real = arr_real.to_tblis_tensor();
+    let (mut tsr_out_re, mut tsr_out_im) = (tsr_out.re(), tsr_out.im());
+    for bits in 0..1_usize << num_complex {
+        // product of units of parts: `1` for real part, `i` (or `-i` if conjugated) for imaginary part
+        let mut factor = scalar;
+        let mut tsr_part = TblisTensor::new(tsr_real.data, shape_out, &tsr_real.stride[..ndim]);
+        for (k, &conj) in conj_list.iter().enumerate() {
+            if bits >> k & 1 == 1 {
+                factor = factor * Complex::new(R::zero(), if conj { -R::one() } else { R::one() });
+                tsr_part.data = unsafe { tsr_part.data.offset(tsr_real.stride[ndim + k]) };
+            }
+        }
+        for (tsr_out_part, alpha) in [(&mut tsr_out_re, factor.re), (&mut tsr_out_im, factor.im)] {
+            if !alpha.is_zero() {
+                let cfg = TblisAddCfgBuilder::default().alpha(alpha).check_overlap(false).build().unwrap();
+                unsafe { tblis_tensor_add_f(&tsr_part, &idx_out, tsr_out_part, &idx_out, Some(cfg))? };
+            }
+        }
+    }
+    drop(arr_real);
+    Ok(arr_out.map(TblisMixedArray::Complex))
+}
+
+/// Split einsum subscripts to input subscripts and explicit output subscript.
+///
+/// For implicit output (no `"->"`), output follows numpy convention: ellipsis (if any) followed
+/// by sorted indices that appear only once.
+fn explicit_subscripts(subscripts: &str) -> (Vec<&str>, String) {
+    let subscripts = subscripts.trim();
+    match subscripts.split_once("->") {
+        Some((inp, out)) => (inp.split(',').map(|s| s.trim()).collect(), out.trim().to_string()),
+        None => {
+            let mut labels = subscripts.chars().filter(|c| c.is_alphabetic()).collect::<Vec<char>>();
+            labels.sort_unstable();
+            let once = labels.iter().filter(|&&c| labels.iter().filter(|&&x| x == c).count() == 1);
+            let ellipsis = if subscripts.contains("...") { "..." } else { "" };
+            (subscripts.split(',').map(|s| s.trim()).collect(), format!("{ellipsis}{}", once.collect::<String>()))
+        },
+    }
+}
+
+/* #endregion */
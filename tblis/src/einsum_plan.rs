@@ -0,0 +1,911 @@
+//! Reusable einsum plan (contraction path and intermediate layouts computed once).
+
+use crate::prelude::*;
+use crate::tensor_ops::check_overlap_f;
+use crate::workspace::TblisSlots;
+use core::marker::PhantomData;
+use opt_einsum_path::typing::{SizeLimitType, TensorShapeType};
+use opt_einsum_path::PathOptimizer;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// (dev-only) Trace (partial sum) of an operand, performed before a pairwise contraction.
+///
+/// - `idx_prev`: einsum subscript of the operand before trace.
+/// - `idx_traced`: einsum subscript of the operand after trace.
+/// - `shape`: shape of the traced tensor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TblisTraceStep {
+    pub idx_prev: String,
+    pub idx_traced: String,
+    pub shape: Vec<isize>,
+}
+
+/// (dev-only) Contraction step of [`TblisEinsumPlan`].
+///
+/// - `contract`: contraction step from path optimization; if trace is performed, `idx_a`/`idx_b`
+///   here is the subscript before trace.
+/// - `trace_a`: trace of the first tensor before contraction (if required).
+/// - `trace_b`: trace of the second tensor before contraction (if required).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TblisPlanStep {
+    pub contract: TblisContractStep,
+    pub trace_a: Option<TblisTraceStep>,
+    pub trace_b: Option<TblisTraceStep>,
+}
+
+/// (dev-only) Index slicing of [`TblisEinsumPlan`], to enforce a hard memory ceiling on
+/// intermediates.
+///
+/// - `indices`: sliced indices (labels after ellipsis expansion) and their dimension sizes.
+/// - `axes`: axes of each sliced index in each operand (empty if not present; more than one for
+///   repeated indices, such as diagonal `ii`).
+/// - `axes_out`: axis of each sliced index in output (`None` if summed).
+/// - `plan`: plan of a single slice, where sliced dimensions are of size 1.
+/// - `max_bytes`: memory ceiling (in bytes) enforced by slicing.
+#[derive(Debug, Clone)]
+pub struct TblisSlicing<T>
+where
+    T: TblisFloatAPI,
+{
+    pub indices: Vec<(char, isize)>,
+    pub axes: Vec<Vec<Vec<usize>>>,
+    pub axes_out: Vec<Option<usize>>,
+    pub plan: TblisEinsumPlan<T>,
+    pub max_bytes: usize,
+}
+
+/// Reusable einsum plan.
+///
+/// Building a plan performs contraction path optimization ([`opt_einsum_path::contract_path`]),
+/// trace decisions and intermediate shape/stride evaluation only once. Executing a plan only
+/// validates operand shapes and runs contraction steps, which can be important when the same
+/// (small) contraction is executed many times.
+///
+/// # Fields
+///
+/// - `subscripts`: einsum subscripts that this plan is built from.
+/// - `shapes`: shapes of input operands.
+/// - `shapes_broadcast`: shapes of input operands after ellipsis broadcasting; dimensions that
+///   differ from `shapes` are broadcasted with stride 0 in execution.
+/// - `row_major`: whether intermediates and internally allocated output are row-major.
+/// - `steps`: contraction steps.
+/// - `shape_out`: shape of the output tensor.
+/// - `slicing`: index slicing, if intermediates do not fit in memory limit (see
+///   [`TblisEinsumPlan::with_memory_ceiling`]).
+///
+/// # Example
+///
+/// ```rust
+/// extern crate tblis_src;
+/// use tblis::prelude::*;
+///
+/// let (nao, nmo): (usize, usize) = (3, 2);
+/// let vec_c: Vec<f64> = (0..nao * nmo).map(|x| x as f64).collect();
+/// let vec_e: Vec<f64> = (0..nao * nao).map(|x| x as f64).collect();
+/// let tsr_c = TblisTensor::new(vec_c.as_ptr() as *mut f64, &[3, 2], &[2, 1]);
+/// let tsr_e = TblisTensor::new(vec_e.as_ptr() as *mut f64, &[3, 3], &[3, 1]);
+///
+/// // path search is only performed here
+/// let plan = TblisEinsumPlan::<f64>::new("μi,μν,νa->ia", &[vec![3, 2], vec![3, 3], vec![3, 2]], "optimal", None, true);
+/// for _ in 0..3 {
+///     let arr_g = unsafe { plan.execute(&[&tsr_c, &tsr_e, &tsr_c], None, None).unwrap() };
+///     assert_eq!(arr_g.shape(), &[2, 2]);
+///     assert_eq!(arr_g.size(), 4);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TblisEinsumPlan<T>
+where
+    T: TblisFloatAPI,
+{
+    pub subscripts: String,
+    pub shapes: Vec<Vec<isize>>,
+    pub shapes_broadcast: Vec<Vec<isize>>,
+    pub row_major: bool,
+    pub steps: Vec<TblisPlanStep>,
+    pub shape_out: Vec<isize>,
+    pub slicing: Option<Box<TblisSlicing<T>>>,
+    // `T` is only used recursively by `slicing`, which does not constrain it
+    _phantom: PhantomData<T>,
+}
+
+impl<T> TblisEinsumPlan<T>
+where
+    T: TblisFloatAPI,
+{
+    /// Build einsum plan.
+    ///
+    /// # Parameters
+    ///
+    /// - `subscripts`: einsum subscripts, e.g. `"ij,jk->ik"`; ellipsis is allowed, e.g.
+    ///   `"...ij,...jk->...ik"`.
+    /// - `shapes`: shapes of input tensors.
+    /// - `optimize`: contraction path optimization strategy (see
+    ///   [`opt_einsum_path::contract_path`]); explicit path can be given by [`TblisExplicitPath`]
+    ///   (or [`TblisEinsumPlan::from_path`]).
+    /// - `memory_limit`: memory limit (number of elements) for contraction path optimization (see
+    ///   [`opt_einsum_path::contract_path`]). An explicit numeric limit is also enforced as hard
+    ///   ceiling of intermediates by index slicing (see [`TblisEinsumPlan::with_memory_ceiling`]);
+    ///   `None` and `MaxInput` do not perform slicing.
+    /// - `row_major`: whether the intermediate tensors and internally allocated output are in
+    ///   row-major (C-style) or col-major (Fortran-style).
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if failed. Use [`TblisEinsumPlan::new_f`] for fallible version.
+    pub fn new(
+        subscripts: &str,
+        shapes: &[TensorShapeType],
+        optimize: impl PathOptimizer,
+        memory_limit: impl Into<SizeLimitType>,
+        row_major: bool,
+    ) -> Self {
+        Self::new_f(subscripts, shapes, optimize, memory_limit, row_major).unwrap()
+    }
+
+    /// Build einsum plan.
+    ///
+    /// # See also
+    ///
+    /// [`TblisEinsumPlan::new`] for non-fallible version.
+    pub fn new_f(
+        subscripts: &str,
+        shapes: &[TensorShapeType],
+        optimize: impl PathOptimizer,
+        memory_limit: impl Into<SizeLimitType>,
+        row_major: bool,
+    ) -> Result<Self, TblisError> {
+        let memory_limit: SizeLimitType = memory_limit.into();
+        let (subscripts_expanded, shapes_expanded) = tblis_einsum_expand_f(subscripts, shapes)?;
+        let contract_steps = tblis_einsum_prep_f(&subscripts_expanded, &shapes_expanded, optimize, memory_limit)?;
+        let mut steps = Vec::with_capacity(contract_steps.len());
+        let shapes_broadcast: Vec<Vec<isize>> =
+            shapes_expanded.iter().map(|shape| shape.iter().map(|&s| s as isize).collect()).collect();
+        // track shapes of tensors during contraction, to evaluate shape of traced tensors
+        let mut shape_list = shapes_broadcast.clone();
+        for contract in contract_steps {
+            let TblisContractStep { indices, idx_a, idx_b, idx_c, shape_c } = &contract;
+            let (trace_a, trace_b) = if let Some(idx_b) = idx_b {
+                // handle trace here
+                // - tblis_tensor_mult is not able to handle combined contract with trace
+                // - trace means something like "eca, ab -> e", where 'c' in first and 'b' in second are redundant
+                //   indices, should be traced before contraction
+                let idx_a_set = idx_a.chars().collect::<BTreeSet<char>>();
+                let idx_b_set = idx_b.chars().collect::<BTreeSet<char>>();
+                let idx_c_set = idx_c.chars().collect::<BTreeSet<char>>();
+                let idx_a_only = &(&idx_a_set - &idx_b_set) - &idx_c_set;
+                let idx_b_only = &(&idx_b_set - &idx_a_set) - &idx_c_set;
+                let trace_a = plan_trace(idx_a, &idx_a_only, &shape_list[indices[0]]);
+                let trace_b = plan_trace(idx_b, &idx_b_only, &shape_list[indices[1]]);
+                (trace_a, trace_b)
+            } else {
+                (None, None)
+            };
+            shape_list.push(shape_c.clone());
+            let mut indices = indices.clone();
+            indices.sort_unstable_by(|a, b| b.cmp(a));
+            for i in indices {
+                shape_list.remove(i);
+            }
+            steps.push(TblisPlanStep { contract, trace_a, trace_b });
+        }
+        let shape_out = shape_list
+            .pop()
+            .ok_or(TblisError::UnsupportedContraction("No contraction step is generated.".to_string()))?;
+        let shapes = shapes.iter().map(|shape| shape.iter().map(|&s| s as isize).collect()).collect();
+        let plan = Self {
+            subscripts: subscripts.to_string(),
+            shapes,
+            shapes_broadcast,
+            row_major,
+            steps,
+            shape_out,
+            slicing: None,
+            _phantom: PhantomData,
+        };
+        // only explicit numeric limit is enforced by slicing; `MaxInput` only guides path optimization
+        match memory_limit {
+            SizeLimitType::Size(size) => {
+                plan.with_memory_ceiling_f((size.max(0.0) as usize).saturating_mul(size_of::<T>()))
+            },
+            _ => Ok(plan),
+        }
+    }
+
+    /// Enforce hard memory ceiling (in bytes) on intermediates by index slicing.
+    ///
+    /// If any intermediate (including traced operands, excluding the output) is larger than
+    /// `max_bytes`, some indices are sliced, and the contraction is performed as a loop over
+    /// slices, with results written (or accumulated, for summed indices) into the output. Sliced
+    /// indices are chosen greedily, each time the index that mostly reduces the largest
+    /// intermediate. Contraction path is kept the same.
+    ///
+    /// Note that the output itself is not sliced away; if the output is allocated internally, it
+    /// is allocated as a whole.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate tblis_src;
+    /// use tblis::prelude::*;
+    ///
+    /// let shapes = [vec![4, 50], vec![50, 60], vec![60, 4]];
+    /// let plan = TblisEinsumPlan::<f64>::new("ij,jk,kl->il", &shapes, "optimal", None, true);
+    /// assert!(plan.largest_intermediate() > 100);
+    /// let plan = plan.with_memory_ceiling(100 * size_of::<f64>());
+    /// assert!(plan.largest_intermediate() <= 100);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if failed. Use [`TblisEinsumPlan::with_memory_ceiling_f`] for
+    ///   fallible version.
+    pub fn with_memory_ceiling(self, max_bytes: usize) -> Self {
+        self.with_memory_ceiling_f(max_bytes).unwrap()
+    }
+
+    /// Enforce hard memory ceiling (in bytes) on intermediates by index slicing.
+    ///
+    /// # Errors
+    ///
+    /// [`TblisError::MemoryLimit`] if some intermediate could not fit in `max_bytes` even if all of
+    /// its indices are sliced (i.e. `max_bytes` is smaller than a single element).
+    ///
+    /// # See also
+    ///
+    /// [`TblisEinsumPlan::with_memory_ceiling`] for non-fallible version.
+    pub fn with_memory_ceiling_f(mut self, max_bytes: usize) -> Result<Self, TblisError> {
+        let shapes: Vec<TensorShapeType> =
+            self.shapes.iter().map(|shape| shape.iter().map(|&s| s as usize).collect()).collect();
+        let (subscripts, shapes_expanded) = tblis_einsum_expand_f(&self.subscripts, &shapes)?;
+        let (inputs, output) = subscripts.split_once("->").unwrap();
+        let inputs: Vec<&str> = inputs.split(',').collect();
+        let mut dims = BTreeMap::new();
+        for (subscript, shape) in inputs.iter().zip(&shapes_expanded) {
+            dims.extend(subscript.chars().zip(shape.iter().map(|&s| s as isize)));
+        }
+
+        // greedy choice of sliced indices
+        let max_elements = max_bytes / size_of::<T>();
+        let intermediates = self.intermediate_subscripts();
+        let mut sliced = vec![];
+        loop {
+            let sizes: Vec<usize> = intermediates.iter().map(|idx| subscript_size(idx, &dims)).collect();
+            let largest = sizes.iter().copied().max().unwrap_or(0);
+            if largest <= max_elements {
+                break;
+            }
+            // candidate indices are those of intermediates exceeding the limit
+            let candidates: BTreeSet<char> = intermediates
+                .iter()
+                .zip(&sizes)
+                .filter(|&(_, &size)| size > max_elements)
+                .flat_map(|(idx, _)| idx.chars())
+                .filter(|c| dims[c] > 1)
+                .collect();
+            let cost = |c: char| {
+                let mut dims = dims.clone();
+                dims.insert(c, 1);
+                let sizes = intermediates.iter().map(|idx| subscript_size(idx, &dims));
+                (sizes.clone().max().unwrap_or(0), sizes.sum::<usize>())
+            };
+            let Some(c) = candidates.into_iter().min_by_key(|&c| cost(c)) else {
+                let required = largest * size_of::<T>();
+                return Err(TblisError::MemoryLimit { required, limit: max_bytes });
+            };
+            sliced.push((c, dims[&c]));
+            dims.insert(c, 1);
+        }
+        if sliced.is_empty() {
+            self.slicing = None;
+            return Ok(self);
+        }
+
+        // plan of a single slice, with the same contraction path
+        let shapes_sliced: Vec<TensorShapeType> =
+            inputs.iter().map(|subscript| subscript.chars().map(|c| dims[&c] as usize).collect()).collect();
+        let path = self.steps.iter().map(|step| step.contract.indices.clone()).collect();
+        let plan = Self::new_f(&subscripts, &shapes_sliced, TblisExplicitPath(path), None, self.row_major)?;
+        let axes = inputs
+            .iter()
+            .map(|subscript| {
+                let axes_of = |c: char| subscript.chars().enumerate().filter(move |&(_, x)| x == c).map(|(i, _)| i);
+                sliced.iter().map(|&(c, _)| axes_of(c).collect()).collect()
+            })
+            .collect();
+        let axes_out = sliced.iter().map(|&(c, _)| output.chars().position(|x| x == c)).collect();
+        self.slicing = Some(Box::new(TblisSlicing { indices: sliced, axes, axes_out, plan, max_bytes }));
+        Ok(self)
+    }
+
+    /// Number of elements of the largest intermediate allocated in execution.
+    ///
+    /// Intermediates include traced operands, and exclude the output. If index slicing is
+    /// performed, this is the largest intermediate of a single slice.
+    pub fn largest_intermediate(&self) -> usize {
+        if let Some(slicing) = &self.slicing {
+            return slicing.plan.largest_intermediate();
+        }
+        let num_steps = self.steps.len();
+        let mut sizes = vec![];
+        for (idx_step, step) in self.steps.iter().enumerate() {
+            let traces = step.trace_a.iter().chain(&step.trace_b);
+            sizes.extend(traces.map(|trace| trace.shape.iter().product::<isize>()));
+            if idx_step != num_steps - 1 {
+                sizes.push(step.contract.shape_c.iter().product());
+            }
+        }
+        sizes.into_iter().max().unwrap_or(0) as usize
+    }
+
+    /// Number of elements of workspace buffers required in execution (see [`TblisWorkspace`]).
+    pub(crate) fn workspace_sizes(&self) -> Vec<usize> {
+        self.workspace_profile().0
+    }
+
+    /// (dev-only) Simulate slot assignment of workspace buffers in execution.
+    ///
+    /// Returns number of elements required by each slot, and total number of elements held by
+    /// workspace after each step (buffers are never freed during execution).
+    fn workspace_profile(&self) -> (Vec<usize>, Vec<usize>) {
+        if let Some(slicing) = &self.slicing {
+            return slicing.plan.workspace_profile();
+        }
+        let mut slots = TblisSlots::default();
+        let mut sizes: Vec<usize> = vec![];
+        let take = |slots: &mut TblisSlots, sizes: &mut Vec<usize>, shape: &[isize]| {
+            let slot = slots.take();
+            let size = shape.iter().product::<isize>().max(0) as usize;
+            match sizes.get_mut(slot) {
+                Some(s) => *s = size.max(*s),
+                None => sizes.push(size),
+            }
+            slot
+        };
+        let mut slot_list: Vec<Option<usize>> = vec![None; self.shapes.len()];
+        let mut held = vec![];
+        let num_steps = self.steps.len();
+        for (idx_step, step) in self.steps.iter().enumerate() {
+            let TblisPlanStep { contract, trace_a, trace_b, .. } = step;
+            let slot_c = (idx_step != num_steps - 1).then(|| take(&mut slots, &mut sizes, &contract.shape_c));
+            for (&i, trace) in contract.indices.iter().zip([trace_a, trace_b]) {
+                if let Some(trace) = trace {
+                    let slot = take(&mut slots, &mut sizes, &trace.shape);
+                    if let Some(slot) = slot_list[i].replace(slot) {
+                        slots.give(slot);
+                    }
+                }
+            }
+            slot_list.push(slot_c);
+            let mut indices = contract.indices.clone();
+            indices.sort_unstable_by(|a, b| b.cmp(a));
+            for i in indices {
+                if let Some(slot) = slot_list.remove(i) {
+                    slots.give(slot);
+                }
+            }
+            held.push(sizes.iter().sum());
+        }
+        (sizes, held)
+    }
+
+    /// Number of elements of workspace required in execution (see [`TblisWorkspace`]).
+    pub fn workspace_size(&self) -> usize {
+        self.workspace_sizes().iter().sum()
+    }
+
+    /// Peak memory (in bytes) allocated when executing the plan with an empty workspace.
+    ///
+    /// This counts workspace buffers of intermediates (including traced operands), which are
+    /// kept until execution finishes, and the output if `alloc_output` (output not
+    /// pre-allocated). Alignment padding of allocations is not counted. For the peak actually
+    /// measured in execution, see [`TblisWorkspace::peak_bytes`].
+    pub fn peak_bytes(&self, alloc_output: bool) -> usize {
+        self.step_bytes(alloc_output).last().copied().unwrap_or(0)
+    }
+
+    /// Check that executing the plan with an empty workspace fits in memory budget (in bytes).
+    ///
+    /// # Errors
+    ///
+    /// [`TblisError::MemoryBudget`] with the first step (index in [`TblisEinsumPlan::steps`];
+    /// of the inner plan if index slicing is performed) at which memory allocated exceeds
+    /// `budget`.
+    pub fn check_memory_budget_f(&self, budget: usize, alloc_output: bool) -> Result<(), TblisError> {
+        self.check_memory_budget_held_f(budget, alloc_output, 0)
+    }
+
+    /// Check memory budget, with `held` bytes already allocated before execution.
+    pub(crate) fn check_memory_budget_held_f(
+        &self,
+        budget: usize,
+        alloc_output: bool,
+        held: usize,
+    ) -> Result<(), TblisError> {
+        for (step, bytes) in self.step_bytes(alloc_output).into_iter().enumerate() {
+            let required = held + bytes;
+            if required > budget {
+                return Err(TblisError::MemoryBudget { step, required, budget });
+            }
+        }
+        Ok(())
+    }
+
+    /// Memory (in bytes) allocated after each step of execution with an empty workspace.
+    fn step_bytes(&self, alloc_output: bool) -> Vec<usize> {
+        let (_, held) = self.workspace_profile();
+        let size_out = self.shape_out.iter().product::<isize>().max(0) as usize;
+        // output is allocated before the slice loop, or at the last step of unsliced execution
+        let step_out = if self.slicing.is_some() { 0 } else { held.len().saturating_sub(1) };
+        let elem = core::mem::size_of::<T>();
+        held.into_iter()
+            .enumerate()
+            .map(|(step, n)| (n + if alloc_output && step >= step_out { size_out } else { 0 }) * elem)
+            .collect()
+    }
+
+    /// Subscripts of intermediates (traced operands and outputs of non-final steps).
+    fn intermediate_subscripts(&self) -> Vec<&str> {
+        let num_steps = self.steps.len();
+        let mut subscripts = vec![];
+        for (idx_step, step) in self.steps.iter().enumerate() {
+            let traces = step.trace_a.iter().chain(&step.trace_b);
+            subscripts.extend(traces.map(|trace| trace.idx_traced.as_str()));
+            if idx_step != num_steps - 1 {
+                subscripts.push(step.contract.idx_c.as_str());
+            }
+        }
+        subscripts
+    }
+
+    /// Build einsum plan from explicit (user-supplied) contraction path.
+    ///
+    /// Path optimization is bypassed. See [`TblisExplicitPath`] for convention of `path`. Note
+    /// that for no more than two operands, the contraction is always performed in one step.
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if failed. Use [`TblisEinsumPlan::from_path_f`] for fallible
+    ///   version.
+    pub fn from_path(subscripts: &str, shapes: &[TensorShapeType], path: &[Vec<usize>], row_major: bool) -> Self {
+        Self::from_path_f(subscripts, shapes, path, row_major).unwrap()
+    }
+
+    /// Build einsum plan from explicit (user-supplied) contraction path.
+    ///
+    /// # Errors
+    ///
+    /// [`TblisError::InvalidPath`] if the path is not valid for the number of operands.
+    ///
+    /// # See also
+    ///
+    /// [`TblisEinsumPlan::from_path`] for non-fallible version.
+    pub fn from_path_f(
+        subscripts: &str,
+        shapes: &[TensorShapeType],
+        path: &[Vec<usize>],
+        row_major: bool,
+    ) -> Result<Self, TblisError> {
+        tblis_einsum_check_path_f(path, shapes.len())?;
+        Self::new_f(subscripts, shapes, TblisExplicitPath(path.to_vec()), None, row_major)
+    }
+
+    /// Execute einsum plan.
+    ///
+    /// # Parameters
+    ///
+    /// - `operands`: list of input tensors; shapes must be the same to those when building the
+    ///   plan. Fields `conj` and `scalar` of each operand are honored (see [`tblis_einsum`]).
+    /// - `out`: pre-allocated output tensor. If `None`, the output tensor is allocated internally.
+    /// - `cfg`: Optional configuration for the operation (see [`TblisEinsumCfg`]).
+    ///
+    /// # Returns
+    ///
+    /// Same to [`tblis_einsum`].
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if failed. Use [`TblisEinsumPlan::execute_f`] for fallible
+    ///   version.
+    ///
+    /// # Safety
+    ///
+    /// - This function does not check tensor data validity and mutability.
+    pub unsafe fn execute(
+        &self,
+        operands: &[&TblisTensor<T>],
+        out: Option<&mut TblisTensor<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+    ) -> Option<TblisArray<T>> {
+        unsafe { self.execute_f(operands, out, cfg).unwrap() }
+    }
+
+    /// Execute einsum plan.
+    ///
+    /// # Safety
+    ///
+    /// - This function does not check tensor data validity and mutability.
+    ///
+    /// # See also
+    ///
+    /// [`TblisEinsumPlan::execute`] for non-fallible version.
+    pub unsafe fn execute_f(
+        &self,
+        operands: &[&TblisTensor<T>],
+        out: Option<&mut TblisTensor<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+    ) -> Result<Option<TblisArray<T>>, TblisError> {
+        let mut workspace = match cfg.as_ref().and_then(|cfg| cfg.allocator.clone()) {
+            Some(allocator) => TblisWorkspace::with_allocator(allocator),
+            None => TblisWorkspace::new(),
+        };
+        unsafe { self.execute_with_workspace_f(operands, out, cfg, &mut workspace) }
+    }
+
+    /// Execute einsum plan, with intermediates in workspace.
+    ///
+    /// Buffers of intermediates are taken from `workspace`, and kept there after execution for
+    /// later reuse (see [`TblisWorkspace`]). Other parameters and returns are the same to
+    /// [`TblisEinsumPlan::execute`].
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if failed. Use [`TblisEinsumPlan::execute_with_workspace_f`] for
+    ///   fallible version.
+    ///
+    /// # Safety
+    ///
+    /// - This function does not check tensor data validity and mutability.
+    pub unsafe fn execute_with_workspace(
+        &self,
+        operands: &[&TblisTensor<T>],
+        out: Option<&mut TblisTensor<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+        workspace: &mut TblisWorkspace<T>,
+    ) -> Option<TblisArray<T>> {
+        unsafe { self.execute_with_workspace_f(operands, out, cfg, workspace).unwrap() }
+    }
+
+    /// Execute einsum plan, with intermediates in workspace.
+    ///
+    /// # Safety
+    ///
+    /// - This function does not check tensor data validity and mutability.
+    ///
+    /// # See also
+    ///
+    /// [`TblisEinsumPlan::execute_with_workspace`] for non-fallible version.
+    pub unsafe fn execute_with_workspace_f(
+        &self,
+        operands: &[&TblisTensor<T>],
+        out: Option<&mut TblisTensor<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+        workspace: &mut TblisWorkspace<T>,
+    ) -> Result<Option<TblisArray<T>>, TblisError> {
+        self.check_operands(operands)?;
+        if let Some(budget) = cfg.as_ref().and_then(|cfg| cfg.memory_budget) {
+            self.check_memory_budget_f(budget, out.is_none())?;
+        }
+        if let (Some(out), true) = (&out, cfg.as_ref().is_none_or(|cfg| cfg.check_overlap)) {
+            check_overlap_f(out, operands)?;
+        }
+        let result = unsafe { self.execute_impl_f(operands, out, cfg, workspace) };
+        workspace.end_execution();
+        result
+    }
+
+    /// Execute einsum plan, with intermediates in workspace (operands and budget checked).
+    pub(crate) unsafe fn execute_impl_f(
+        &self,
+        operands: &[&TblisTensor<T>],
+        out: Option<&mut TblisTensor<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+        workspace: &mut TblisWorkspace<T>,
+    ) -> Result<Option<TblisArray<T>>, TblisError> {
+        if let Some(slicing) = &self.slicing {
+            return unsafe { self.execute_sliced_f(slicing, operands, out, cfg, workspace) };
+        }
+        // no intermediates are alive before execution
+        workspace.release_all();
+        let TblisEinsumCfg { alpha: alpha_out, beta: beta_out, allocator, check_overlap, .. } = cfg.unwrap_or_default();
+        let allocator = allocator.unwrap_or_else(|| workspace.allocator().clone());
+        if let Some(out) = &out {
+            if out.shape != self.shape_out {
+                return Err(TblisError::OutputMismatch { expected: self.shape_out.clone(), found: out.shape.clone() });
+            }
+        }
+        // output allocated internally is uninitialized, beta is not applicable
+        let beta_out = if out.is_some() { beta_out } else { T::zero() };
+        // tensors with workspace slot (if intermediate)
+        let mut tensor_list: Vec<(TblisTensor<T>, Option<usize>)> =
+            operands.iter().zip(&self.shapes_broadcast).map(|(&t, shape)| (broadcast_tensor(t, shape), None)).collect();
+        // scalars of operands are folded into alpha of the final step; conjugation is applied when
+        // each operand is consumed, so intermediates are always plain data
+        let mut scalar_operands = T::one();
+        for (tsr, _) in tensor_list.iter_mut() {
+            scalar_operands = scalar_operands * tsr.scalar;
+            tsr.scalar = T::one();
+        }
+        let alpha_out = alpha_out * scalar_operands;
+        #[cfg(feature = "debug_checks")]
+        let inputs_finite = {
+            let operands: Vec<&TblisTensor<T>> = tensor_list.iter().map(|(tsr, _)| tsr).collect();
+            crate::debug_checks::inputs_finite(&operands, alpha_out, beta_out, out.as_deref())
+        };
+        let num_steps = self.steps.len();
+        let mut arr_out = None;
+        for (idx_step, step) in self.steps.iter().enumerate() {
+            let TblisPlanStep { contract, trace_a, trace_b, .. } = step;
+            let TblisContractStep { indices, idx_a, idx_b, idx_c, shape_c } = contract;
+            let is_last_step = idx_step == num_steps - 1;
+            let (alpha, beta) = if is_last_step { (alpha_out, beta_out) } else { (T::one(), T::zero()) };
+            let (slot_c, mut tsr_c) = match (is_last_step, &out) {
+                // final tensor with pre-allocated space
+                (true, Some(tsr_c)) => (None, (*tsr_c).clone()),
+                // final tensor without pre-allocated space (returned to caller)
+                (true, None) => {
+                    let mut arr_c = unsafe { TblisArray::uninit_in_f(shape_c, self.row_major, &*allocator)? };
+                    workspace.add_output_bytes(arr_c.capacity() * core::mem::size_of::<T>());
+                    let tsr_c = arr_c.to_tblis_tensor_mut();
+                    arr_out = Some(arr_c);
+                    (None, tsr_c)
+                },
+                // intermediate tensor
+                (false, _) => {
+                    let (slot_c, tsr_c) = unsafe { workspace.take_f(shape_c, self.row_major)? };
+                    (Some(slot_c), tsr_c)
+                },
+            };
+
+            if let Some(idx_b) = idx_b {
+                // case of tensor mult
+                let mut idx_a = idx_a.as_str();
+                let mut idx_b = idx_b.as_str();
+                if let Some(trace_a) = trace_a {
+                    let traced = tblis_trace_f(trace_a, &tensor_list[indices[0]].0, self.row_major, workspace)?;
+                    if let (_, Some(slot)) = core::mem::replace(&mut tensor_list[indices[0]], traced) {
+                        workspace.give(slot);
+                    }
+                    idx_a = &trace_a.idx_traced;
+                }
+                if let Some(trace_b) = trace_b {
+                    let traced = tblis_trace_f(trace_b, &tensor_list[indices[1]].0, self.row_major, workspace)?;
+                    if let (_, Some(slot)) = core::mem::replace(&mut tensor_list[indices[1]], traced) {
+                        workspace.give(slot);
+                    }
+                    idx_b = &trace_b.idx_traced;
+                }
+
+                let tsr_a = &tensor_list[indices[0]].0;
+                let tsr_b = &tensor_list[indices[1]].0;
+                // handle empty idx_a/idx_b (scalar-like operations)
+                match (idx_a.is_empty(), idx_b.is_empty()) {
+                    (false, false) => unsafe {
+                        let mult_cfg = TblisMultCfgBuilder::default()
+                            .alpha(alpha)
+                            .beta(beta)
+                            .conja(tsr_a.conj)
+                            .conjb(tsr_b.conj)
+                            .check_overlap(check_overlap)
+                            .build()
+                            .unwrap();
+                        tblis_tensor_mult_f(tsr_a, idx_a, tsr_b, idx_b, &mut tsr_c, idx_c, Some(mult_cfg))?;
+                    },
+                    (true, true) => {
+                        let val_a = tsr_a.clone().set_scalar(T::one()).to_scalar()?;
+                        let val_b = tsr_b.clone().set_scalar(T::one()).to_scalar()?;
+                        let val_c = alpha * val_a * val_b;
+                        // uninitialized data should not be read when beta is zero
+                        let val_c = if beta == T::zero() { val_c } else { val_c + beta * unsafe { *tsr_c.data } };
+                        unsafe { *tsr_c.data = val_c };
+                    },
+                    (true, false) => {
+                        let val_a = tsr_a.clone().set_scalar(T::one()).to_scalar()?;
+                        let add_cfg = TblisAddCfgBuilder::default()
+                            .alpha(alpha * val_a)
+                            .beta(beta)
+                            .conja(tsr_b.conj)
+                            .check_overlap(check_overlap)
+                            .build()
+                            .unwrap();
+                        unsafe { tblis_tensor_add_f(tsr_b, idx_b, &mut tsr_c, idx_c, Some(add_cfg))? };
+                    },
+                    (false, true) => {
+                        let val_b = tsr_b.clone().set_scalar(T::one()).to_scalar()?;
+                        let add_cfg = TblisAddCfgBuilder::default()
+                            .alpha(alpha * val_b)
+                            .beta(beta)
+                            .conja(tsr_a.conj)
+                            .check_overlap(check_overlap)
+                            .build()
+                            .unwrap();
+                        unsafe { tblis_tensor_add_f(tsr_a, idx_a, &mut tsr_c, idx_c, Some(add_cfg))? };
+                    },
+                };
+                tensor_list.push((tsr_c, slot_c));
+            } else {
+                // case of tensor transpose (implement by add)
+                let tsr_a = &tensor_list[indices[0]].0;
+                let cfg = TblisAddCfgBuilder::default()
+                    .alpha(alpha)
+                    .beta(beta)
+                    .conja(tsr_a.conj)
+                    .check_overlap(check_overlap)
+                    .build()
+                    .unwrap();
+                unsafe { tblis_tensor_add_f(tsr_a, idx_a, &mut tsr_c, idx_c, Some(cfg))? };
+                tensor_list.push((tsr_c, slot_c));
+            }
+            #[cfg(feature = "debug_checks")]
+            if inputs_finite {
+                let (tsr_c, _) = tensor_list.last().unwrap();
+                crate::debug_checks::check_finite_f(tsr_c, || format!("output {idx_c:?} of einsum step {idx_step}"))?;
+            }
+            // remove used tensors
+            let mut indices = indices.to_vec();
+            indices.sort_unstable_by(|a, b| b.cmp(a));
+            for i in indices {
+                if let (_, Some(slot)) = tensor_list.remove(i) {
+                    workspace.give(slot);
+                }
+            }
+        }
+        assert!(tensor_list.len() == 1);
+        Ok(arr_out)
+    }
+
+    /// Execute einsum plan as a loop over slices.
+    unsafe fn execute_sliced_f(
+        &self,
+        slicing: &TblisSlicing<T>,
+        operands: &[&TblisTensor<T>],
+        out: Option<&mut TblisTensor<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+        workspace: &mut TblisWorkspace<T>,
+    ) -> Result<Option<TblisArray<T>>, TblisError> {
+        let TblisSlicing { indices, axes, axes_out, plan, .. } = slicing;
+        let TblisEinsumCfg { alpha, beta, allocator, check_overlap, .. } = cfg.unwrap_or_default();
+        let allocator = allocator.unwrap_or_else(|| workspace.allocator().clone());
+        let (arr_out, tsr_out, beta) = match out {
+            Some(out) => {
+                if out.shape != self.shape_out {
+                    let (expected, found) = (self.shape_out.clone(), out.shape.clone());
+                    return Err(TblisError::OutputMismatch { expected, found });
+                }
+                (None, out.clone(), beta)
+            },
+            None => {
+                // output allocated internally is uninitialized, beta is not applicable
+                let mut arr_out = unsafe { TblisArray::uninit_in_f(&self.shape_out, self.row_major, &*allocator)? };
+                workspace.add_output_bytes(arr_out.capacity() * core::mem::size_of::<T>());
+                let tsr_out = arr_out.to_tblis_tensor_mut();
+                (Some(arr_out), tsr_out, T::zero())
+            },
+        };
+        let operands: Vec<TblisTensor<T>> =
+            operands.iter().zip(&self.shapes_broadcast).map(|(&t, shape)| broadcast_tensor(t, shape)).collect();
+
+        let num_slices = indices.iter().map(|&(_, size)| size as usize).product::<usize>();
+        let mut values = vec![0isize; indices.len()];
+        for idx_slice in 0..num_slices {
+            if idx_slice > 0 {
+                // next multi-index (last index runs fastest)
+                for k in (0..values.len()).rev() {
+                    values[k] += 1;
+                    if values[k] < indices[k].1 {
+                        break;
+                    }
+                    values[k] = 0;
+                }
+            }
+            let operands_slice: Vec<TblisTensor<T>> = operands
+                .iter()
+                .zip(axes)
+                .map(|(tsr, axes)| slice_tensor(tsr, axes.iter().map(Vec::as_slice), &values))
+                .collect();
+            let operands_slice: Vec<&TblisTensor<T>> = operands_slice.iter().collect();
+            let mut tsr_out_slice = slice_tensor(&tsr_out, axes_out.iter().map(Option::as_slice), &values);
+            // the first slice of summed indices initializes the output region, the others accumulate
+            let first = values.iter().zip(axes_out).all(|(&v, axis)| axis.is_some() || v == 0);
+            let beta = if first { beta } else { T::one() };
+            let cfg =
+                TblisEinsumCfgBuilder::default().alpha(alpha).beta(beta).check_overlap(check_overlap).build().unwrap();
+            unsafe { plan.execute_impl_f(&operands_slice, Some(&mut tsr_out_slice), Some(cfg), workspace)? };
+        }
+        Ok(arr_out)
+    }
+
+    /// Check that operands are consistent to the shapes when building the plan.
+    fn check_operands(&self, operands: &[&TblisTensor<T>]) -> Result<(), TblisError> {
+        self.check_shapes(&operands.iter().map(|tsr| tsr.shape.as_slice()).collect::<Vec<_>>())
+    }
+
+    /// Check that shapes of operands are consistent to the shapes when building the plan.
+    pub(crate) fn check_shapes(&self, shapes: &[&[isize]]) -> Result<(), TblisError> {
+        if shapes.len() != self.shapes.len() {
+            return Err(TblisError::OperandCount { expected: self.shapes.len(), found: shapes.len() });
+        }
+        for (i, (&found, shape)) in shapes.iter().zip(self.shapes.iter()).enumerate() {
+            if found != shape.as_slice() {
+                return Err(TblisError::OperandShape { operand: i, expected: shape.clone(), found: found.to_vec() });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Broadcast size-1 dimensions of tensor to the given shape by stride 0.
+pub(crate) fn broadcast_tensor<T>(tsr: &TblisTensor<T>, shape: &[isize]) -> TblisTensor<T>
+where
+    T: TblisFloatAPI,
+{
+    let mut tsr = tsr.clone();
+    for (i, &s) in shape.iter().enumerate() {
+        if tsr.shape[i] != s {
+            tsr.shape[i] = s;
+            tsr.stride[i] = 0;
+        }
+    }
+    tsr
+}
+
+/// Take slice of tensor at `values` along `axes` (all axes of each sliced index, empty for not
+/// present), keeping the sliced dimensions as size 1.
+fn slice_tensor<'a, T>(
+    tsr: &TblisTensor<T>,
+    axes: impl IntoIterator<Item = &'a [usize]>,
+    values: &[isize],
+) -> TblisTensor<T>
+where
+    T: TblisFloatAPI,
+{
+    let mut tsr = tsr.clone();
+    for (axes, &v) in axes.into_iter().zip(values) {
+        for &axis in axes {
+            tsr.data = unsafe { tsr.data.offset(v * tsr.stride[axis]) };
+            tsr.shape[axis] = 1;
+        }
+    }
+    tsr
+}
+
+/// Number of elements of tensor with the given subscript.
+fn subscript_size(subscript: &str, dims: &BTreeMap<char, isize>) -> usize {
+    subscript.chars().map(|c| dims[&c] as usize).product()
+}
+
+/// Decide whether trace is required for indices that are unique to one operand.
+fn plan_trace(idx_prev: &str, idx_only: &BTreeSet<char>, shape_prev: &[isize]) -> Option<TblisTraceStep> {
+    if idx_only.is_empty() {
+        return None;
+    }
+    let idx_prev_chars: Vec<char> = idx_prev.chars().collect();
+    let idx_traced = idx_prev.chars().filter(|c| !idx_only.contains(c)).collect::<String>();
+    let shape = idx_traced
+        .chars()
+        .map(|c| shape_prev[idx_prev_chars.iter().position(|&x| x == c).unwrap()])
+        .collect::<Vec<isize>>();
+    Some(TblisTraceStep { idx_prev: idx_prev.to_string(), idx_traced, shape })
+}
+
+/// Perform trace operation on a tensor. This can be used when [`tblis_tensor_mult`] could not
+/// handle the case where `idx_a` or `idx_b` contains redundant indices.
+///
+/// Conjugation of `tsr_prev` is applied, so the traced tensor is plain data.
+fn tblis_trace_f<T>(
+    trace: &TblisTraceStep,
+    tsr_prev: &TblisTensor<T>,
+    row_major: bool,
+    workspace: &mut TblisWorkspace<T>,
+) -> Result<(TblisTensor<T>, Option<usize>), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    if trace.idx_prev.chars().count() != tsr_prev.shape.len() {
+        return Err(TblisError::SubscriptLength { subscript: trace.idx_prev.clone(), shape: tsr_prev.shape.clone() });
+    }
+    let (slot, mut tsr_traced) = unsafe { workspace.take_f(&trace.shape, row_major)? };
+    let cfg = TblisAddCfgBuilder::default().beta(T::zero()).conja(tsr_prev.conj).build().unwrap();
+    unsafe { tblis_tensor_add_f(tsr_prev, &trace.idx_prev, &mut tsr_traced, &trace.idx_traced, Some(cfg))? };
+    Ok((tsr_traced, Some(slot)))
+}
@@ -0,0 +1,291 @@
+//! Einsum with one operand streamed in chunks (batches) along one index.
+
+#![allow(clippy::too_many_arguments)]
+
+use crate::einsum_plan::broadcast_tensor;
+use crate::prelude::*;
+use crate::tensor_ops::check_overlap_f;
+use opt_einsum_path::typing::SizeLimitType;
+use opt_einsum_path::PathOptimizer;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Streamed operand of [`tblis_einsum_stream`].
+///
+/// - `operand`: position of the streamed operand in einsum subscripts.
+/// - `index`: streamed index; must appear exactly once in subscript of the streamed operand (it may
+///   be repeated in other operands, which are chunked at all of its occurrences).
+/// - `shape`: full shape of the streamed operand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TblisStreamSpec {
+    pub operand: usize,
+    pub index: char,
+    pub shape: Vec<isize>,
+}
+
+/// Perform einsum operation with one operand produced in chunks along one index.
+///
+/// This is useful when one operand (e.g. electronic integrals $E_{\mu \nu \kappa \lambda}$) does
+/// not fit in memory, and is produced in batches (e.g. over $\mu$). Each chunk is contracted
+/// with the corresponding slices of other operands, and the result is accumulated (with beta =
+/// 1) into the output.
+///
+/// Contraction path (and `memory_limit`) is resolved only once for the full shapes. Plans are built
+/// once for each distinct chunk length, and intermediate buffers are reused for every chunk.
+///
+/// # Parameters
+///
+/// - `subscripts`: einsum subscripts, e.g. `"μi,μνκλ->iνκλ"`.
+/// - `operands`: list of input tensors, excluding the streamed operand.
+/// - `stream`: position, streamed index and full shape of the streamed operand (see
+///   [`TblisStreamSpec`]).
+/// - `producer`: callback yielding chunks `(offset, tensor)`, where `tensor` is the slice
+///   `offset..offset + len` of the streamed operand along the streamed index; `None` when
+///   exhausted. Chunks should not overlap. Data of each chunk must be valid until the next call to
+///   `producer`.
+/// - `optimize`, `memory_limit`, `row_major`, `out`, `cfg`: same to [`tblis_einsum`].
+///
+/// # Returns
+///
+/// Same to [`tblis_einsum`]. Regions of output not covered by any chunk are zero (or scaled by
+/// `beta` for pre-allocated output).
+///
+/// # Example
+///
+/// ```rust
+/// extern crate tblis_src;
+/// use tblis::prelude::*;
+///
+/// let (nao, nmo): (usize, usize) = (3, 2);
+/// let vec_c: Vec<f64> = (0..nao * nmo).map(|x| x as f64).collect();
+/// let vec_e: Vec<f64> = (0..nao.pow(4)).map(|x| x as f64).collect();
+/// let tsr_c = TblisTensor::new(vec_c.as_ptr() as *mut f64, &[3, 2], &[2, 1]);
+/// let tsr_e = TblisTensor::new(vec_e.as_ptr() as *mut f64, &[3, 3, 3, 3], &[27, 9, 3, 1]);
+/// let subscripts = "μi,νa,μνκλ,κj,λb->iajb";
+/// let operands = [&tsr_c, &tsr_c, &tsr_e, &tsr_c, &tsr_c];
+/// let arr_ref = unsafe { tblis_einsum(subscripts, &operands, "optimal", None, true, None, None).unwrap() };
+///
+/// // produce E in batches of 2 over μ
+/// let stream = TblisStreamSpec { operand: 2, index: 'μ', shape: vec![3, 3, 3, 3] };
+/// let mut offset = 0;
+/// let producer = || {
+///     if offset >= 3 {
+///         return None;
+///     }
+///     let len = 2.min(3 - offset);
+///     let chunk = TblisTensor::new(unsafe { tsr_e.data.offset(offset * 27) }, &[len, 3, 3, 3], &[27, 9, 3, 1]);
+///     offset += len;
+///     Some((offset - len, chunk))
+/// };
+/// let operands = [&tsr_c, &tsr_c, &tsr_c, &tsr_c];
+/// let arr = unsafe { tblis_einsum_stream(subscripts, &operands, &stream, producer, "optimal", None, true, None, None) };
+/// let arr = arr.unwrap();
+/// for (x, y) in arr.as_slice().iter().zip(arr_ref.as_slice()) {
+///     assert!((x - y).abs() < 1e-8);
+/// }
+/// ```
+///
+/// # Panics
+///
+/// - This function will panic if failed. Use [`tblis_einsum_stream_f`] for fallible version.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+pub unsafe fn tblis_einsum_stream<T>(
+    subscripts: &str,
+    operands: &[&TblisTensor<T>],
+    stream: &TblisStreamSpec,
+    producer: impl FnMut() -> Option<(isize, TblisTensor<T>)>,
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+    row_major: bool,
+    out: Option<&mut TblisTensor<T>>,
+    cfg: Option<TblisEinsumCfg<T>>,
+) -> Option<TblisArray<T>>
+where
+    T: TblisFloatAPI,
+{
+    unsafe {
+        tblis_einsum_stream_f(subscripts, operands, stream, producer, optimize, memory_limit, row_major, out, cfg)
+            .unwrap()
+    }
+}
+
+/// Perform einsum operation with one operand produced in chunks along one index.
+///
+/// # Errors
+///
+/// - [`TblisError::UnsupportedContraction`] if the streamed index does not appear exactly once in
+///   subscript of the streamed operand.
+/// - [`TblisError::OperandShape`] if a chunk does not match the full shape of the streamed operand,
+///   or is out of bounds.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// [`tblis_einsum_stream`] for non-fallible version.
+pub unsafe fn tblis_einsum_stream_f<T>(
+    subscripts: &str,
+    operands: &[&TblisTensor<T>],
+    stream: &TblisStreamSpec,
+    mut producer: impl FnMut() -> Option<(isize, TblisTensor<T>)>,
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+    row_major: bool,
+    out: Option<&mut TblisTensor<T>>,
+    cfg: Option<TblisEinsumCfg<T>>,
+) -> Result<Option<TblisArray<T>>, TblisError>
+where
+    T: TblisFloatAPI,
+{
+    let TblisStreamSpec { operand: pos, index, shape } = stream;
+    let (pos, index) = (*pos, *index);
+    if pos > operands.len() {
+        return Err(TblisError::OperandCount { expected: pos + 1, found: operands.len() + 1 });
+    }
+    let memory_limit: SizeLimitType = memory_limit.into();
+
+    // plan of full shapes, for contraction path and output shape
+    let mut shapes: Vec<Vec<usize>> = operands.iter().map(|t| t.shape.iter().map(|&s| s as usize).collect()).collect();
+    shapes.insert(pos, shape.iter().map(|&s| s as usize).collect());
+    let plan = TblisEinsumPlan::<T>::new_f(subscripts, &shapes, optimize, memory_limit, row_major)?;
+    let path: Vec<Vec<usize>> = plan.steps.iter().map(|step| step.contract.indices.clone()).collect();
+    let (subscripts, _) = tblis_einsum_expand_f(subscripts, &shapes)?;
+    let (inputs, output) = subscripts.split_once("->").unwrap();
+    let inputs: Vec<&str> = inputs.split(',').collect();
+    if inputs[pos].chars().filter(|&c| c == index).count() != 1 {
+        let msg = format!("Streamed index {index} must appear exactly once in subscript {:?}.", inputs[pos]);
+        return Err(TblisError::UnsupportedContraction(msg));
+    }
+    // repeated stream index (e.g. diagonal) of other operands is chunked at all occurrences
+    let axes: Vec<Vec<usize>> =
+        inputs.iter().map(|s| s.chars().enumerate().filter(|&(_, c)| c == index).map(|(i, _)| i).collect()).collect();
+    let axes_out: Vec<usize> = output.chars().position(|c| c == index).into_iter().collect();
+    let axis = axes[pos][0];
+    // memory limit is resolved once for the full shapes, not for each chunk length: path of chunks
+    // is fixed, so only an explicit numeric limit (enforced by index slicing) applies to chunks
+    let memory_limit_chunk = match memory_limit {
+        SizeLimitType::Size(size) => SizeLimitType::Size(size),
+        _ => SizeLimitType::None,
+    };
+
+    // initialize output, then accumulate all chunks
+    let TblisEinsumCfg { alpha, beta, allocator, memory_budget, check_overlap, .. } = cfg.unwrap_or_default();
+    let allocator: Arc<dyn TblisAllocator<T>> = allocator.unwrap_or_else(|| Arc::new(TblisDefaultAllocator));
+    if let Some(out) = &out {
+        if out.shape != plan.shape_out {
+            return Err(TblisError::OutputMismatch { expected: plan.shape_out.clone(), found: out.shape.clone() });
+        }
+    }
+    let (arr_out, tsr_out) = match out {
+        Some(out) => {
+            // operands must not be destroyed by initialization of output
+            if check_overlap {
+                check_overlap_f(out, operands).map_err(|err| match err {
+                    TblisError::Aliasing { operand } if operand >= pos => TblisError::Aliasing { operand: operand + 1 },
+                    err => err,
+                })?;
+            }
+            // initialization works on a local copy, so that metadata of caller's tensor is kept
+            let mut tsr_out = out.clone();
+            if beta == T::zero() {
+                let cfg = TblisSetCfgBuilder::default().check_overlap(check_overlap).build().unwrap();
+                unsafe { tblis_tensor_set_f(&mut tsr_out, output, T::zero(), Some(cfg))? };
+            } else if beta != T::one() {
+                let cfg = TblisScaleCfgBuilder::default().alpha(beta).check_overlap(check_overlap).build().unwrap();
+                unsafe { tblis_tensor_scale_f(&mut tsr_out, output, Some(cfg))? };
+            }
+            (None, tsr_out)
+        },
+        None => {
+            let required = plan.shape_out.iter().product::<isize>().max(0) as usize * core::mem::size_of::<T>();
+            if let Some(budget) = memory_budget.filter(|&budget| required > budget) {
+                return Err(TblisError::MemoryBudget { step: 0, required, budget });
+            }
+            let mut arr_out = unsafe { TblisArray::uninit_in_f(&plan.shape_out, row_major, &*allocator)? };
+            arr_out.as_mut_slice().fill(T::zero());
+            let tsr_out = arr_out.to_tblis_tensor_mut();
+            (Some(arr_out), tsr_out)
+        },
+    };
+    let mut operands: Vec<Option<TblisTensor<T>>> = operands
+        .iter()
+        .zip(plan.shapes_broadcast.iter().enumerate().filter(|&(i, _)| i != pos))
+        .map(|(&t, (_, shape))| Some(broadcast_tensor(t, shape)))
+        .collect();
+    operands.insert(pos, None);
+
+    let mut plans: BTreeMap<isize, TblisEinsumPlan<T>> = BTreeMap::new();
+    let mut workspace = TblisWorkspace::with_allocator(allocator);
+    while let Some((offset, chunk)) = producer() {
+        let len = chunk.shape.get(axis).copied().unwrap_or(-1);
+        let shape_match = chunk.shape.len() == shape.len()
+            && chunk.shape.iter().zip(shape).enumerate().all(|(i, (&s, &full))| i == axis || s == full);
+        if !shape_match || len < 0 || offset < 0 || offset + len > shape[axis] {
+            return Err(TblisError::OperandShape { operand: pos, expected: shape.clone(), found: chunk.shape.clone() });
+        }
+        if len == 0 {
+            continue;
+        }
+        let plan_chunk = match plans.entry(len) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let shapes_chunk: Vec<Vec<usize>> = plan
+                    .shapes_broadcast
+                    .iter()
+                    .zip(&axes)
+                    .map(|(shape, axes)| {
+                        let mut shape: Vec<usize> = shape.iter().map(|&s| s as usize).collect();
+                        axes.iter().for_each(|&axis| shape[axis] = len as usize);
+                        shape
+                    })
+                    .collect();
+                let path = TblisExplicitPath(path.clone());
+                let plan_chunk =
+                    TblisEinsumPlan::new_f(&subscripts, &shapes_chunk, path, memory_limit_chunk, row_major)?;
+                if let Some(budget) = memory_budget {
+                    // output (if allocated internally) is alive in all chunks
+                    let held = arr_out.as_ref().map_or(0, |arr| arr.size() * core::mem::size_of::<T>());
+                    plan_chunk.check_memory_budget_held_f(budget, false, held)?;
+                }
+                entry.insert(plan_chunk)
+            },
+        };
+
+        let operands_chunk: Vec<TblisTensor<T>> = operands
+            .iter()
+            .zip(&axes)
+            .zip(&plan_chunk.shapes_broadcast)
+            .map(|((tsr, axes), shape)| match tsr {
+                Some(tsr) => chunk_tensor(tsr, axes, offset, len),
+                None => broadcast_tensor(&chunk, shape),
+            })
+            .collect();
+        let operands_chunk: Vec<&TblisTensor<T>> = operands_chunk.iter().collect();
+        let mut tsr_out_chunk = chunk_tensor(&tsr_out, &axes_out, offset, len);
+        let cfg =
+            TblisEinsumCfgBuilder::default().alpha(alpha).beta(T::one()).check_overlap(check_overlap).build().unwrap();
+        unsafe {
+            plan_chunk.execute_with_workspace_f(&operands_chunk, Some(&mut tsr_out_chunk), Some(cfg), &mut workspace)?
+        };
+    }
+    Ok(arr_out)
+}
+
+/// Take range `offset..offset + len` of tensor along all of `axes` (no-op if empty).
+fn chunk_tensor<T>(tsr: &TblisTensor<T>, axes: &[usize], offset: isize, len: isize) -> TblisTensor<T>
+where
+    T: TblisFloatAPI,
+{
+    let mut tsr = tsr.clone();
+    for &axis in axes {
+        tsr.data = unsafe { tsr.data.offset(offset * tsr.stride[axis]) };
+        tsr.shape[axis] = len;
+    }
+    tsr
+}
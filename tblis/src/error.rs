@@ -0,0 +1,136 @@
+//! Error type of this crate.
+
+use crate::tensor_ops::TblisReduceOp;
+use core::fmt::{Display, Formatter};
+
+/// Error type for fallible functions (functions with `_f` suffix) of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TblisError {
+    /// Number of tensors (operands) does not match the number of subscripts (or the plan).
+    OperandCount { expected: usize, found: usize },
+    /// Length of subscript does not match the number of dimensions of tensor.
+    SubscriptLength { subscript: String, shape: Vec<isize> },
+    /// Dimension size of tensor is negative.
+    InvalidDimension { index: char, size: isize, subscript: String },
+    /// The same index corresponds to different dimension sizes.
+    ShapeMismatch { index: char, expected: isize, found: isize, subscript: String },
+    /// Shape of operand does not match the shape when building the plan.
+    OperandShape { operand: usize, expected: Vec<isize>, found: Vec<isize> },
+    /// Indices unique to some tensors are not allowed by the operation.
+    ///
+    /// `unique` gives the unique indices of each tensor, in the same order of `subscripts`.
+    UniqueIndex { func: &'static str, subscripts: Vec<String>, unique: Vec<String> },
+    /// Too many distinct indices to be represented by `c_char` for TBLIS.
+    TooManyLabels { count: usize, max: usize },
+    /// Memory allocation failed (or memory layout is invalid).
+    Allocation(String),
+    /// Intermediate tensor (in bytes) could not fit in memory limit, even with index slicing.
+    MemoryLimit { required: usize, limit: usize },
+    /// Memory allocated in einsum execution (bytes, up to the step) exceeds user-given budget.
+    MemoryBudget { step: usize, required: usize, budget: usize },
+    /// Subscripts parsing or contraction path optimization failed (error from crate
+    /// `opt-einsum-path`).
+    PathOptimization(String),
+    /// User-supplied contraction path is not valid for the operands.
+    InvalidPath { path: Vec<Vec<usize>>, msg: String },
+    /// Reduction operation is not supported for the given tensor or function.
+    UnsupportedReduction { op: TblisReduceOp, msg: String },
+    /// Contraction is not supported by TBLIS.
+    UnsupportedContraction(String),
+    /// Pre-allocated output tensor does not match the expected shape.
+    OutputMismatch { expected: Vec<isize>, found: Vec<isize> },
+    /// Layout (shape and stride) is invalid or out of bounds of the buffer of length `len`.
+    InvalidLayout { shape: Vec<isize>, stride: Vec<isize>, len: usize },
+    /// Layout of output tensor is self-overlapping (different indices refer to the same memory).
+    SelfOverlap { shape: Vec<isize>, stride: Vec<isize> },
+    /// Memory of output tensor overlaps with input tensor (index among inputs of the operation).
+    Aliasing { operand: usize },
+    /// Tensor is not a scalar (size is not 1).
+    NotScalar { shape: Vec<isize> },
+    /// Output contains non-finite values while all inputs are finite, which indicates elements left
+    /// unwritten (crate feature `debug_checks`).
+    NonFinite(String),
+    /// TBLIS shared library could not be loaded (crate feature `dynamic_loading`).
+    LibraryLoad(String),
+}
+
+impl Display for TblisError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        use TblisError::*;
+        match self {
+            OperandCount { expected, found } => {
+                write!(f, "Number of operands does not match: expected {expected}, found {found}")
+            },
+            SubscriptLength { subscript, shape } => {
+                write!(f, "Subscript length and shape length do not match: {subscript:?} vs {shape:?}")
+            },
+            InvalidDimension { index, size, subscript } => {
+                write!(f, "Invalid dimension size {size} for index {index} in subscript {subscript:?}")
+            },
+            ShapeMismatch { index, expected, found, subscript } => {
+                write!(
+                    f,
+                    "Inconsistent dimension size for index {index}: {expected} vs {found} in subscript {subscript:?}"
+                )
+            },
+            OperandShape { operand, expected, found } => {
+                write!(f, "Shape of operand {operand} does not match the plan: expected {expected:?}, found {found:?}")
+            },
+            UniqueIndex { func, subscripts, unique } => {
+                write!(
+                    f,
+                    "{func}: Unique indices are not allowed. Subscripts: {subscripts:?}, unique indices: {unique:?}"
+                )
+            },
+            TooManyLabels { count, max } => {
+                write!(f, "Too many unique characters in indices ({count} > {max})")
+            },
+            Allocation(msg) => write!(f, "Allocation failed: {msg}"),
+            MemoryLimit { required, limit } => {
+                write!(f, "Intermediate of {required} bytes exceeds memory limit of {limit} bytes")
+            },
+            MemoryBudget { step, required, budget } => {
+                write!(f, "Einsum step {step} requires {required} bytes, exceeding memory budget of {budget} bytes")
+            },
+            PathOptimization(msg) => write!(f, "Contraction path optimization failed: {msg}"),
+            InvalidPath { path, msg } => write!(f, "Invalid contraction path {path:?}: {msg}"),
+            UnsupportedReduction { op, msg } => write!(f, "Unsupported reduction {op:?}: {msg}"),
+            UnsupportedContraction(msg) => write!(f, "Unsupported contraction: {msg}"),
+            OutputMismatch { expected, found } => {
+                write!(f, "Output tensor shape mismatch: expected {expected:?}, found {found:?}")
+            },
+            InvalidLayout { shape, stride, len } => {
+                write!(f, "Invalid layout for buffer of length {len}: shape {shape:?}, stride {stride:?}")
+            },
+            SelfOverlap { shape, stride } => {
+                write!(f, "Layout of output tensor is self-overlapping: shape {shape:?}, stride {stride:?}")
+            },
+            Aliasing { operand } => write!(f, "Memory of output tensor overlaps with input tensor {operand}"),
+            NotScalar { shape } => write!(f, "Tensor is not a scalar, shape {shape:?}"),
+            NonFinite(msg) => write!(f, "Non-finite values in {msg}, while all inputs are finite"),
+            LibraryLoad(msg) => write!(f, "Unable to load TBLIS library: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for TblisError {}
+
+/// Check whether the TBLIS shared library can be dynamically loaded.
+///
+/// Library loading failure in crate `tblis-ffi` panics on the first TBLIS call; this function
+/// captures that panic and returns [`TblisError::LibraryLoad`] instead.
+#[cfg(feature = "dynamic_loading")]
+pub fn tblis_dyload_check_f() -> Result<(), TblisError> {
+    std::panic::catch_unwind(|| {
+        unsafe { tblis_ffi::tblis::dyload_lib() };
+    })
+    .map_err(|e| {
+        let msg = match (e.downcast_ref::<String>(), e.downcast_ref::<&str>()) {
+            (Some(s), _) => s.to_string(),
+            (None, Some(s)) => s.to_string(),
+            (None, None) => "unknown error".to_string(),
+        };
+        TblisError::LibraryLoad(msg)
+    })
+}
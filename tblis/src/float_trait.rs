@@ -11,8 +11,20 @@ use num::Complex;
 pub trait TblisFloatAPI: ComplexFloat {
     /// TBLIS constant indicating type in FFI.
     const TYPE: tblis_ffi::tblis::type_t;
+    /// Whether the type is complex (by default, size of type differs from its real type).
+    const IS_COMPLEX: bool = core::mem::size_of::<Self>() != core::mem::size_of::<Self::Real>();
     /// Convert to scalar type of TBLIS FFI.
     fn to_ffi_scalar(&self) -> tblis_ffi::tblis::tblis_scalar;
+    /// Convert from scalar type of TBLIS FFI.
+    ///
+    /// # Panics
+    ///
+    /// - If type of `scalar` is not [`TblisFloatAPI::TYPE`].
+    fn from_ffi_scalar(scalar: &tblis_ffi::tblis::tblis_scalar) -> Self {
+        assert_eq!(scalar.type_, Self::TYPE, "type of TBLIS scalar does not match");
+        // all fields of the union start at the same address
+        unsafe { core::mem::transmute_copy(&scalar.data) }
+    }
 }
 
 #[duplicate_item(
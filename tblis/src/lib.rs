@@ -7,6 +7,9 @@
 //! Most functions in this crate are marked as `unsafe` because they do not check the validity of
 //! tensor data and mutability.
 //!
+//! For safe operations, use borrow-checked views [`TblisView`] and [`TblisViewMut`] created from
+//! slices, and the functions taking them (such as [`tblis_einsum_view`] and [`tblis_view_mult`]).
+//!
 //! ## About function [`tblis_einsum`]
 //!
 //! This is the most important function of this crate.
@@ -19,7 +22,11 @@
 //! |--|--|
 //! | fn [`tblis_einsum`] | Einstein summation |
 //! | fn [`tblis_einsum_ndarray`] | Einstein summation with ndarray |
-//! | struct [`TblisTensor`] | Tensor struct of this crate |
+//! | struct [`TblisTensor`] | Tensor struct of this crate (memory overlap by [`TblisTensor::memory_extent`], [`TblisTensor::overlaps`]) |
+//! | struct [`TblisArray`] | Owned (64-byte aligned) tensor, returned by [`tblis_einsum`] |
+//! | struct [`TblisEinsumPlan`] | Reusable einsum plan (path optimization performed once; index slicing by [`TblisEinsumPlan::with_memory_ceiling`]) |
+//! | struct [`TblisWorkspace`] | Buffer arena for intermediates, reused across executions of [`TblisEinsumPlan`] (measured peak memory by [`TblisWorkspace::peak_bytes`]) |
+//! | struct [`TblisView`]<br>struct [`TblisViewMut`] | Borrow-checked tensor views for safe operations |
 //! | trait [`TblisFloatAPI`] | Float trait for TBLIS operations (f32, f64, c32, c64) |
 //!
 //! ## Functions
@@ -29,14 +36,23 @@
 //! | [`tblis_einsum`] | (high-level) Einstein summation |
 //! | [`tblis_einsum_ndarray`] | (high-level) Einstein summation with ndarray |
 //! | [`tblis_einsum_f`] | (high-level) Einstein summation (failable) |
+//! | [`tblis_einsum_path`] | (high-level) Contraction path report of einsum ([`TblisEinsumPath`], equivalent to `numpy.einsum_path`) |
+//! | [`tblis_einsum_mixed`] | (high-level) Einstein summation with mixed real and complex operands |
+//! | [`tblis_einsum_split`] | (high-level) Einstein summation on split-complex tensors |
+//! | [`tblis_einsum_stream`] | (high-level) Einstein summation with one operand produced in chunks |
 //! | [`tblis_einsum_ndarray_f`] | (high-level) Einstein summation with ndarray (failable) |
 //! | [`tblis_tensor_add`] | (1t-level) $B = \alpha A + \beta B$ |
 //! | [`tblis_tensor_scale`] | (1t-level) $A = \alpha A$ |
 //! | [`tblis_tensor_set`] | (1t-level) $A = \alpha$ |
 //! | [`tblis_tensor_shift`] | (1t-level) $A = \alpha + \beta A$ |
 //! | [`tblis_tensor_reduce`] | (1t-level) $\gamma = \mathrm{op}(A)$ |
+//! | [`tblis_tensor_reduce_with_index`] | (1t-level) $\gamma = \mathrm{op}(A)$ with location of result (max/min) |
+//! | [`tblis_tensor_norm`] | (1t-level) $\gamma = \Vert A \Vert$ as real value (norm1, norm2, norminf) |
+//! | [`tblis_tensor_reduce_partial`] | (1t-level) $B = \mathrm{op}(A)$ over indices not in $B$ (e.g. `"ijk->i"`) |
 //! | [`tblis_tensor_dot`] | (1t-level) $\gamma = A B$ |
 //! | [`tblis_tensor_mult`] | (3t-level) $C = \alpha A B + \beta C$ |
+//! | [`tblis_split_tensor_add`]<br>[`tblis_split_tensor_mult`] | Addition and multiplication on split-complex tensors ([`TblisSplitTensor`]) |
+//! | [`tblis_einsum_view`]<br>[`tblis_view_add`], [`tblis_view_mult`], ... | Safe counterparts of the functions above on [`TblisView`] / [`TblisViewMut`] |
 //! | [`tblis_get_num_threads`] | Get the number of threads used by TBLIS |
 //! | [`tblis_set_num_threads`] | Set the number of threads used by TBLIS |
 //!
@@ -46,7 +62,14 @@
 //! |--|--|
 //! | [`TblisFloatAPI`] | Float trait for TBLIS operations (f32, f64, c32, c64) |
 //! | [`ToTblisTensor`] | Tensor view convert to TBLIS (mutable) tensor<br>Method function [`ToTblisTensor::to_tblis_tensor`] |
-//! | [`ArrayFromTblisTensor`] | Convert from TBLIS tensor to ndarray object<br>Method function [`ArrayFromTblisTensor::into_array`]<br>Only available for crate feature `ndarray` |
+//! | [`ArrayFromTblisTensor`] | Convert from TBLIS tensor (or [`TblisArray`], without copy) to ndarray object<br>Method function [`ArrayFromTblisTensor::into_array`]<br>Only available for crate feature `ndarray` |
+//! | [`TblisAllocator`] | Allocator of einsum intermediates and outputs (owned storage [`TblisBuffer`])<br>Default implementation [`TblisDefaultAllocator`] (64-byte aligned)<br>Huge page implementation [`TblisHugePageAllocator`] (Linux)<br>Used in [`TblisEinsumCfg`], [`TblisWorkspace::with_allocator`] |
+//!
+//! ## Errors
+//!
+//! | Item | Description |
+//! |--|--|
+//! | [`TblisError`] | Error type of fallible functions (functions with `_f` suffix) |
 //!
 //! ## Configurations
 //!
@@ -57,25 +80,47 @@
 //! | [`TblisUniCfg`]<br>[`TblisUniCfgBuilder`] | One parameter configuration for TBLIS operations<br>- By default $\alpha = 1$, no conjugate<br>- Used in [`tblis_tensor_scale`], [`tblis_tensor_shift`], [`tblis_tensor_reduce`]<br>- Aliased as [`TblisScaleCfg`], [`TblisShiftCfg`], [`TblisReduceCfg`] |
 //! | [`TblisBiCfg`]<br>[`TblisBiCfgBuilder`] | Two parameter configuration for TBLIS operations<br>- By default $\alpha = 1, \beta = 1$, no conjugate<br>- Used in [`tblis_tensor_add`], [`tblis_tensor_dot`]<br>- Aliased as [`TblisAddCfg`], [`TblisDotCfg`] |
 //! | [`TblisTriCfg`]<br>[`TblisTriCfgBuilder`] | Three parameter configuration for TBLIS operations<br>- By default $\alpha = 1, \beta = 0$, no conjugate<br>- Used in [`tblis_tensor_mult`]<br>- Aliased as [`TblisMultCfg`] |
+//! | [`TblisEinsumCfg`]<br>[`TblisEinsumCfgBuilder`] | Configuration for einsum $C = \alpha \, \mathrm{einsum}(\cdots) + \beta C$<br>- By default $\alpha = 1, \beta = 0$, default allocator, no memory budget<br>- Used in [`tblis_einsum`], [`TblisEinsumPlan::execute`] |
+//! | [`TblisExplicitPath`] | Explicit (user-supplied) contraction path, bypassing path optimization<br>- Used as `optimize` in [`tblis_einsum`], or [`TblisEinsumPlan::from_path`] |
 #![doc = include_str!("../readme.md")]
 
 pub mod alloc_vec;
 pub mod char_parse;
 pub mod containers;
 pub mod einsum_impl;
+pub mod einsum_mixed;
+pub mod einsum_plan;
+pub mod einsum_stream;
+pub mod error;
 pub mod float_trait;
+pub mod split_complex;
 pub mod tensor_ops;
 pub mod threading;
+pub mod views;
+pub mod workspace;
 
 #[cfg(feature = "ndarray")]
 pub mod ndarray_impl;
 
+#[cfg(feature = "debug_checks")]
+pub(crate) mod debug_checks;
+
 pub mod prelude {
+    pub use crate::alloc_vec::{
+        TblisAllocator, TblisBuffer, TblisDefaultAllocator, TblisHugePageAllocator, TblisHugePageAllocatorBuilder,
+    };
     pub use crate::containers::*;
     pub use crate::einsum_impl::*;
+    pub use crate::einsum_mixed::*;
+    pub use crate::einsum_plan::*;
+    pub use crate::einsum_stream::*;
+    pub use crate::error::*;
     pub use crate::float_trait::*;
+    pub use crate::split_complex::*;
     pub use crate::tensor_ops::*;
     pub use crate::threading::*;
+    pub use crate::views::*;
+    pub use crate::workspace::*;
 
     #[cfg(feature = "ndarray")]
     pub use crate::ndarray_impl::*;
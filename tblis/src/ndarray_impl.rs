@@ -21,7 +21,7 @@ where
     }
 }
 
-/// Trait to convert `(Vec<T>, TblisTensor<T>)` to [`ndarray::ArrayD`].
+/// Trait to convert [`TblisArray<T>`] (or `(Vec<T>, TblisTensor<T>)`) to [`ndarray::ArrayD`].
 pub trait ArrayFromTblisTensor {
     type Out;
     fn into_array(self) -> Self::Out;
@@ -39,6 +39,28 @@ where
     }
 }
 
+impl<T> ArrayFromTblisTensor for TblisArray<T>
+where
+    T: TblisFloatAPI,
+{
+    type Out = ArrayD<T>;
+    /// Convert to [`ndarray::ArrayD`] without copying data (if storage is a vector of the global
+    /// allocator; otherwise data is copied, see [`TblisBuffer::into_vec`]).
+    #[allow(deprecated)] // `into_shape` is deprecated in ndarray 0.16, but is the only choice for 0.15
+    fn into_array(self) -> ArrayD<T> {
+        let size = self.size();
+        let row_major = self.is_row_major();
+        let (data, shape, _) = self.into_raw_parts();
+        let (vec, offset) = data.into_vec_offset();
+        let arr = Array1::from_vec(vec).slice_move(s![offset..offset + size]);
+        let shape = shape.iter().map(|&s| s as usize);
+        match row_major {
+            true => arr.into_shape(IxDyn(&shape.collect::<Vec<usize>>())).unwrap(),
+            false => arr.into_shape(IxDyn(&shape.rev().collect::<Vec<usize>>())).unwrap().reversed_axes(),
+        }
+    }
+}
+
 /// Convert `(Vec<T>, TblisTensor<T>)` to [`ndarray::ArrayD`].
 pub fn array_from_tblis_tensor<T>(dat: (Vec<T>, TblisTensor<T>)) -> ArrayD<T>
 where
@@ -93,6 +115,7 @@ where
 ///         None,                     // memory limit (None means no limit, see crate opt-einsum-path)
 ///         true,                     // row-major (true) or col-major (false)
 ///         None,                     // pre-allocated output tensor (None to allocate internally)
+///         None,                     // einsum configuration (alpha, beta)
 ///     )
 ///     .unwrap();
 ///
@@ -116,12 +139,13 @@ pub fn tblis_einsum_ndarray<T, A>(
     memory_limit: impl Into<SizeLimitType>,
     row_major: bool,
     out: Option<ArrayViewMutD<T>>,
+    cfg: Option<TblisEinsumCfg<T>>,
 ) -> Option<ArrayD<T>>
 where
     T: TblisFloatAPI,
     A: ToTblisTensor<T>,
 {
-    tblis_einsum_ndarray_f(subscripts, operands, optimize, memory_limit, row_major, out).unwrap()
+    tblis_einsum_ndarray_f(subscripts, operands, optimize, memory_limit, row_major, out, cfg).unwrap()
 }
 
 /// High-level failable Einstein summation interface for [`ndarray::ArrayBase`].
@@ -138,7 +162,8 @@ pub fn tblis_einsum_ndarray_f<T, A>(
     memory_limit: impl Into<SizeLimitType>,
     row_major: bool,
     out: Option<ArrayViewMutD<T>>,
-) -> Result<Option<ArrayD<T>>, String>
+    cfg: Option<TblisEinsumCfg<T>>,
+) -> Result<Option<ArrayD<T>>, TblisError>
 where
     T: TblisFloatAPI,
     A: ToTblisTensor<T>,
@@ -147,7 +172,15 @@ where
     let tblis_operands_ref: Vec<&TblisTensor<T>> = tblis_operands.iter().collect();
     let mut out_tblis_tensor = out.map(|x| x.to_tblis_tensor());
     let res = unsafe {
-        tblis_einsum_f(subscripts, &tblis_operands_ref, optimize, memory_limit, row_major, out_tblis_tensor.as_mut())
+        tblis_einsum_f(
+            subscripts,
+            &tblis_operands_ref,
+            optimize,
+            memory_limit,
+            row_major,
+            out_tblis_tensor.as_mut(),
+            cfg,
+        )
     };
     match res {
         Ok(Some(out)) => Ok(Some(out.into_array())),
@@ -156,6 +189,57 @@ where
     }
 }
 
+impl<T> TblisEinsumPlan<T>
+where
+    T: TblisFloatAPI,
+{
+    /// Execute einsum plan for [`ndarray::ArrayBase`].
+    ///
+    /// This function only works when crate feature `ndarray` is enabled.
+    ///
+    /// # See also
+    ///
+    /// - [`TblisEinsumPlan::execute`] (native TBLIS interface)
+    /// - [`TblisEinsumPlan::execute_ndarray_f`] (failable ndarray interface)
+    pub fn execute_ndarray<A>(
+        &self,
+        operands: &[&A],
+        out: Option<ArrayViewMutD<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+    ) -> Option<ArrayD<T>>
+    where
+        A: ToTblisTensor<T>,
+    {
+        self.execute_ndarray_f(operands, out, cfg).unwrap()
+    }
+
+    /// Execute einsum plan for [`ndarray::ArrayBase`] (failable).
+    ///
+    /// # See also
+    ///
+    /// - [`TblisEinsumPlan::execute_f`] (failable native TBLIS interface)
+    /// - [`TblisEinsumPlan::execute_ndarray`] (ndarray interface)
+    pub fn execute_ndarray_f<A>(
+        &self,
+        operands: &[&A],
+        out: Option<ArrayViewMutD<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+    ) -> Result<Option<ArrayD<T>>, TblisError>
+    where
+        A: ToTblisTensor<T>,
+    {
+        let tblis_operands: Vec<TblisTensor<T>> = operands.iter().map(|x| x.to_tblis_tensor()).collect();
+        let tblis_operands_ref: Vec<&TblisTensor<T>> = tblis_operands.iter().collect();
+        let mut out_tblis_tensor = out.map(|x| x.to_tblis_tensor());
+        let res = unsafe { self.execute_f(&tblis_operands_ref, out_tblis_tensor.as_mut(), cfg) };
+        match res {
+            Ok(Some(out)) => Ok(Some(out.into_array())),
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "ndarray")]
 mod test_ndarray_native {
@@ -188,6 +272,7 @@ mod test_ndarray_native {
                 None,                     // memory limit (None means no limit, see crate opt-einsum-path)
                 true,                     // row-major (true) or col-major (false)
                 None,                     // pre-allocated output tensor (None to allocate internally)
+                None,                     // einsum configuration (alpha, beta)
             )
             .unwrap();
 
@@ -226,16 +311,43 @@ mod test_ndarray_native {
                     None,                     // memory limit (None means no limit, see crate opt-einsum-path)
                     true,                     // row-major (true) or col-major (false)
                     None,                     // pre-allocated output tensor (None to allocate internally)
+                    None,                     // einsum configuration (alpha, beta)
                 )
             };
-            let (vec_g, tsr_g) = out_g.unwrap(); // (underlying data, tensor shape/stride info)
+            let arr_g = out_g.unwrap(); // owned array with underlying data and shape/stride info
 
             // transform tblis object back to ndarray object
-            let arr_g = (vec_g, tsr_g).into_array().into_dimensionality().unwrap();
+            let arr_g = arr_g.into_array().into_dimensionality().unwrap();
             arr_g
         }
 
         let arr_g = ao2mo(arr_c, arr_e);
         println!("{:?}", arr_g);
     }
+
+    #[test]
+    fn test_tblis_array_into_array() {
+        use crate::prelude::*;
+        use ndarray::prelude::*;
+
+        // zero-copy conversion of (aligned) owned array
+        for row_major in [true, false] {
+            let mut arr = TblisArray::<f64>::zeros(&[4, 8, 5], row_major);
+            arr.as_mut_slice().iter_mut().enumerate().for_each(|(i, x)| *x = i as f64);
+            let ptr = arr.as_slice().as_ptr();
+            assert_eq!(ptr as usize % 64, 0);
+            let arr = arr.into_array();
+            assert_eq!(arr.as_ptr(), ptr);
+            assert_eq!(arr.shape(), &[4, 8, 5]);
+            let stride = if row_major { [40, 5, 1] } else { [1, 4, 32] };
+            assert_eq!(arr.strides(), &stride);
+            assert_eq!(arr[[3, 7, 4]], (3 * stride[0] + 7 * stride[1] + 4 * stride[2]) as f64);
+        }
+
+        // scalar and empty arrays
+        let arr = TblisArray::<f64>::from_vec(vec![2.0], &[], true).into_array();
+        assert_eq!(arr.into_dimensionality::<Ix0>().unwrap().into_scalar(), 2.0);
+        let arr = TblisArray::<f64>::zeros(&[3, 0], false).into_array();
+        assert_eq!(arr.shape(), &[3, 0]);
+    }
 }
@@ -0,0 +1,711 @@
+//! Split-complex tensors (real and imaginary parts stored in separate real arrays).
+//!
+//! TBLIS only handles interleaved complex data ([`Complex<R>`]). Operations in this module
+//! decompose complex operations on split-complex tensors into real TBLIS calls on the parts, so
+//! that data does not need to be interleaved first.
+
+use crate::einsum_plan::broadcast_tensor;
+use crate::prelude::*;
+use crate::tensor_ops::check_overlap_f;
+use core::ptr::NonNull;
+use num::{Complex, Float, Zero};
+use opt_einsum_path::typing::SizeLimitType;
+use opt_einsum_path::PathOptimizer;
+use std::sync::Arc;
+
+/* #region containers */
+
+/// Split-complex tensor: real and imaginary parts as two real tensors of the same shape.
+///
+/// Like [`TblisTensor`], this struct does not own the data. Fields `conj` and `scalar` of the
+/// parts are not used; conjugation and scalar multipliers are given by configurations of
+/// operations.
+#[derive(Debug, Clone)]
+pub struct TblisSplitTensor<R>
+where
+    R: TblisFloatAPI + Float,
+{
+    pub re: TblisTensor<R>,
+    pub im: TblisTensor<R>,
+}
+
+impl<R> TblisSplitTensor<R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    /// Create a new split-complex tensor from raw parts; both parts share the same shape and
+    /// stride.
+    pub fn new(re: *mut R, im: *mut R, shape: &[isize], stride: &[isize]) -> Self {
+        Self { re: TblisTensor::new(re, shape, stride), im: TblisTensor::new(im, shape, stride) }
+    }
+
+    /// Create a new split-complex tensor from two real tensors.
+    ///
+    /// # Panics
+    ///
+    /// - If shapes of parts are different. Use [`TblisSplitTensor::from_parts_f`] for fallible
+    ///   version.
+    pub fn from_parts(re: TblisTensor<R>, im: TblisTensor<R>) -> Self {
+        Self::from_parts_f(re, im).unwrap()
+    }
+
+    /// Create a new split-complex tensor from two real tensors (strides may differ).
+    pub fn from_parts_f(re: TblisTensor<R>, im: TblisTensor<R>) -> Result<Self, TblisError> {
+        if re.shape != im.shape {
+            return Err(TblisError::OperandShape { operand: 1, expected: re.shape, found: im.shape });
+        }
+        Ok(Self { re, im })
+    }
+
+    pub fn shape(&self) -> &[isize] {
+        &self.re.shape
+    }
+
+    /// Try to convert to a complex scalar value.
+    pub fn to_scalar(&self) -> Result<Complex<R>, TblisError> {
+        let re = self.re.clone().set_scalar(R::one()).set_conj(false).to_scalar()?;
+        let im = self.im.clone().set_scalar(R::one()).set_conj(false).to_scalar()?;
+        Ok(Complex::new(re, im))
+    }
+}
+
+/// Owned split-complex array; real and imaginary parts are [`TblisArray`] of the same layout.
+#[derive(Debug, Clone)]
+pub struct TblisSplitArray<R>
+where
+    R: TblisFloatAPI + Float,
+{
+    pub re: TblisArray<R>,
+    pub im: TblisArray<R>,
+}
+
+impl<R> TblisSplitArray<R>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    /// Create an uninitialized split-complex array.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that the array is properly initialized before reading it.
+    pub unsafe fn uninit_f(shape: &[isize], row_major: bool) -> Result<Self, TblisError> {
+        unsafe { Self::uninit_in_f(shape, row_major, &TblisDefaultAllocator) }
+    }
+
+    /// Create an uninitialized split-complex array, with storage of both parts from allocator.
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that the array is properly initialized before reading it.
+    pub unsafe fn uninit_in_f(
+        shape: &[isize],
+        row_major: bool,
+        allocator: &(impl TblisAllocator<R> + ?Sized),
+    ) -> Result<Self, TblisError> {
+        let re = unsafe { TblisArray::uninit_in_f(shape, row_major, allocator)? };
+        let im = unsafe { TblisArray::uninit_in_f(shape, row_major, allocator)? };
+        Ok(Self { re, im })
+    }
+
+    /// Create a zero-initialized split-complex array.
+    pub fn zeros(shape: &[isize], row_major: bool) -> Self {
+        Self { re: TblisArray::zeros(shape, row_major), im: TblisArray::zeros(shape, row_major) }
+    }
+
+    pub fn shape(&self) -> &[isize] {
+        self.re.shape()
+    }
+
+    /// Convert to split-complex tensor; the array must outlive it.
+    pub fn to_split_tensor(&self) -> TblisSplitTensor<R> {
+        TblisSplitTensor { re: self.re.to_tblis_tensor(), im: self.im.to_tblis_tensor() }
+    }
+
+    /// Convert to split-complex tensor that is to be written; the array must outlive it.
+    pub fn to_split_tensor_mut(&mut self) -> TblisSplitTensor<R> {
+        TblisSplitTensor { re: self.re.to_tblis_tensor_mut(), im: self.im.to_tblis_tensor_mut() }
+    }
+}
+
+/// (dev-only) Allocator of real parts, backed by allocator of the complex type.
+///
+/// Storage of `size` real elements is allocated as `size.div_ceil(2)` complex elements and
+/// reinterpreted; the complex storage is released when the real storage is dropped.
+pub(crate) struct TblisSplitAllocator<R>(pub(crate) Arc<dyn TblisAllocator<Complex<R>>>)
+where
+    R: TblisFloatAPI + Float;
+
+impl<R> core::fmt::Debug for TblisSplitAllocator<R>
+where
+    R: TblisFloatAPI + Float,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("TblisSplitAllocator").field(&self.0).finish()
+    }
+}
+
+impl<R> TblisAllocator<R> for TblisSplitAllocator<R>
+where
+    R: TblisFloatAPI + Float + Send + Sync + 'static,
+{
+    unsafe fn allocate(&self, size: usize) -> Result<TblisBuffer<R>, TblisError> {
+        let mut data = unsafe { self.0.allocate(size.div_ceil(2))? };
+        // `Complex<R>` is `repr(C)` of two `R`, so storage of complex elements is valid and aligned
+        // for twice as many real elements
+        let ptr = unsafe { NonNull::new_unchecked(data.as_mut_ptr() as *mut R) };
+        let len = data.len() * 2;
+        Ok(unsafe { TblisBuffer::from_raw_parts(ptr, len, move |_, _| drop(data)) })
+    }
+}
+
+/* #endregion */
+
+/* #region add */
+
+/// Split-complex tensor addition $B = \alpha A + \beta B$.
+///
+/// This is decomposed to (at most four) real [`tblis_tensor_add`] calls; two calls if $\alpha$
+/// and $\beta$ are real.
+///
+/// # Parameters
+///
+/// Same to [`tblis_tensor_add`], with complex `alpha` and `beta`; `conja` / `conjb` conjugate the
+/// split-complex tensors.
+///
+/// # Panics
+///
+/// - If the indices and shapes of the tensors are incompatible.
+/// - If both tensors have unique indices (i.e., indices that do not appear in the other tensor).
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_split_tensor_add_f`] for fallible version.
+pub unsafe fn tblis_split_tensor_add<R>(
+    a: &TblisSplitTensor<R>,
+    idx_a: &str,
+    b: &mut TblisSplitTensor<R>,
+    idx_b: &str,
+    cfg: Option<TblisAddCfg<Complex<R>>>,
+) where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    unsafe { tblis_split_tensor_add_f(a, idx_a, b, idx_b, cfg).unwrap() }
+}
+
+/// Split-complex tensor addition $B = \alpha A + \beta B$.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_split_tensor_add`] for infallible version.
+pub unsafe fn tblis_split_tensor_add_f<R>(
+    a: &TblisSplitTensor<R>,
+    idx_a: &str,
+    b: &mut TblisSplitTensor<R>,
+    idx_b: &str,
+    cfg: Option<TblisAddCfg<Complex<R>>>,
+) -> Result<(), TblisError>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    unsafe { split_tensor_add_in_f(a, idx_a, b, idx_b, cfg, &TblisDefaultAllocator) }
+}
+
+/// Split-complex tensor addition, with temporary storage (for complex $\beta$) from `allocator`.
+pub(crate) unsafe fn split_tensor_add_in_f<R>(
+    a: &TblisSplitTensor<R>,
+    idx_a: &str,
+    b: &mut TblisSplitTensor<R>,
+    idx_b: &str,
+    cfg: Option<TblisAddCfg<Complex<R>>>,
+    allocator: &dyn TblisAllocator<R>,
+) -> Result<(), TblisError>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    let TblisAddCfg { comm, cntx, alpha, beta, conja, conjb, check_overlap } = cfg.unwrap_or_default();
+    if check_overlap {
+        check_split_overlap_f(b, &[a])?;
+    }
+    let mut beta_parts = unsafe { split_scale_beta_f(b, idx_b, beta, conjb, allocator)? };
+    let b_parts = [&b.re, &b.im];
+    for (tsr_a, unit_a) in split_parts(a, conja) {
+        let coef = alpha * unit_a;
+        for (k, (tsr_b, coef)) in b_parts.iter().zip([coef.re, coef.im]).enumerate() {
+            if coef.is_zero() {
+                continue;
+            }
+            let (alpha, beta, check_overlap) = (coef, beta_parts[k], false);
+            let cfg = TblisAddCfg { comm, cntx, alpha, beta, conja: false, conjb: false, check_overlap };
+            let mut tsr_b = (*tsr_b).clone();
+            unsafe { tblis_tensor_add_f(tsr_a, idx_a, &mut tsr_b, idx_b, Some(cfg))? };
+            beta_parts[k] = R::one();
+        }
+    }
+    unsafe { split_scale_remaining_f(b, idx_b, beta_parts) }
+}
+
+/* #endregion */
+
+/* #region mult */
+
+/// Split-complex tensor multiplication $C = \alpha A B + \beta C$.
+///
+/// This is decomposed to (at most eight) real [`tblis_tensor_mult`] calls; four calls if $\alpha$
+/// and $\beta$ are real.
+///
+/// # Parameters
+///
+/// Same to [`tblis_tensor_mult`], with complex `alpha` and `beta`; `conja` / `conjb` conjugate
+/// the split-complex tensors.
+///
+/// # Panics
+///
+/// - If the indices and shapes of the tensors are incompatible.
+/// - If either tensors have unique indices (i.e., indices that do not appear in the other two
+///   tensors).
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_split_tensor_mult_f`] for fallible version.
+pub unsafe fn tblis_split_tensor_mult<R>(
+    a: &TblisSplitTensor<R>,
+    idx_a: &str,
+    b: &TblisSplitTensor<R>,
+    idx_b: &str,
+    c: &mut TblisSplitTensor<R>,
+    idx_c: &str,
+    cfg: Option<TblisMultCfg<Complex<R>>>,
+) where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    unsafe { tblis_split_tensor_mult_f(a, idx_a, b, idx_b, c, idx_c, cfg).unwrap() }
+}
+
+/// Split-complex tensor multiplication $C = \alpha A B + \beta C$.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_split_tensor_mult`] for infallible version.
+pub unsafe fn tblis_split_tensor_mult_f<R>(
+    a: &TblisSplitTensor<R>,
+    idx_a: &str,
+    b: &TblisSplitTensor<R>,
+    idx_b: &str,
+    c: &mut TblisSplitTensor<R>,
+    idx_c: &str,
+    cfg: Option<TblisMultCfg<Complex<R>>>,
+) -> Result<(), TblisError>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    unsafe { split_tensor_mult_in_f(a, idx_a, b, idx_b, c, idx_c, cfg, &TblisDefaultAllocator) }
+}
+
+/// Split-complex tensor multiplication, with temporary storage (for complex $\beta$) from
+/// `allocator`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) unsafe fn split_tensor_mult_in_f<R>(
+    a: &TblisSplitTensor<R>,
+    idx_a: &str,
+    b: &TblisSplitTensor<R>,
+    idx_b: &str,
+    c: &mut TblisSplitTensor<R>,
+    idx_c: &str,
+    cfg: Option<TblisMultCfg<Complex<R>>>,
+    allocator: &dyn TblisAllocator<R>,
+) -> Result<(), TblisError>
+where
+    R: TblisFloatAPI + Float,
+    Complex<R>: TblisFloatAPI,
+{
+    let TblisMultCfg { comm, cntx, alpha, beta, conja, conjb, check_overlap } = cfg.unwrap_or_default();
+    if check_overlap {
+        check_split_overlap_f(c, &[a, b])?;
+    }
+    let mut beta_parts = unsafe { split_scale_beta_f(c, idx_c, beta, false, allocator)? };
+    let c_parts = [&c.re, &c.im];
+    for (tsr_a, unit_a) in split_parts(a, conja) {
+        for (tsr_b, unit_b) in split_parts(b, conjb) {
+            let coef = alpha * unit_a * unit_b;
+            for (k, (tsr_c, coef)) in c_parts.iter().zip([coef.re, coef.im]).enumerate() {
+                if coef.is_zero() {
+                    continue;
+                }
+                let (alpha, beta, check_overlap) = (coef, beta_parts[k], false);
+                let cfg = TblisMultCfg { comm, cntx, alpha, beta, conja: false, conjb: false, check_overlap };
+                let mut tsr_c = (*tsr_c).clone();
+                unsafe { tblis_tensor_mult_f(tsr_a, idx_a, tsr_b, idx_b, &mut tsr_c, idx_c, Some(cfg))? };
+                beta_parts[k] = R::one();
+            }
+        }
+    }
+    unsafe { split_scale_remaining_f(c, idx_c, beta_parts) }
+}
+
+/* #endregion */
+
+/* #region einsum */
+
+/// Perform einsum operation on split-complex tensors using TBLIS.
+///
+/// Parameters and returns are the same to [`tblis_einsum`], but with split-complex operands and
+/// output. Each pairwise contraction is decomposed to real TBLIS calls (see
+/// [`tblis_split_tensor_mult`]). Index slicing is not performed, so if an intermediate exceeds a
+/// numeric `memory_limit`, [`TblisError::MemoryLimit`] is returned instead. Field `memory_budget`
+/// of `cfg` is checked against the steps of the plan (see [`TblisEinsumPlan::peak_bytes`]); real
+/// and imaginary parts together take the same memory as complex intermediates. Field `allocator` of
+/// `cfg` allocates storage of both parts (as complex elements, two real elements each).
+///
+/// # Panics
+///
+/// - This function will panic if failed. Use [`tblis_einsum_split_f`] for fallible version.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_einsum_split_f`] for fallible version.
+/// - [`TblisEinsumPlan::execute_split`] for reusable plan.
+pub unsafe fn tblis_einsum_split<R>(
+    subscripts: &str,
+    operands: &[&TblisSplitTensor<R>],
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+    row_major: bool,
+    out: Option<&mut TblisSplitTensor<R>>,
+    cfg: Option<TblisEinsumCfg<Complex<R>>>,
+) -> Option<TblisSplitArray<R>>
+where
+    R: TblisFloatAPI + Float + Send + Sync + 'static,
+    Complex<R>: TblisFloatAPI,
+{
+    unsafe { tblis_einsum_split_f(subscripts, operands, optimize, memory_limit, row_major, out, cfg).unwrap() }
+}
+
+/// Perform einsum operation on split-complex tensors using TBLIS.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_einsum_split`] for non-fallible version.
+pub unsafe fn tblis_einsum_split_f<R>(
+    subscripts: &str,
+    operands: &[&TblisSplitTensor<R>],
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+    row_major: bool,
+    out: Option<&mut TblisSplitTensor<R>>,
+    cfg: Option<TblisEinsumCfg<Complex<R>>>,
+) -> Result<Option<TblisSplitArray<R>>, TblisError>
+where
+    R: TblisFloatAPI + Float + Send + Sync + 'static,
+    Complex<R>: TblisFloatAPI,
+{
+    let shapes: Vec<Vec<usize>> = operands.iter().map(|t| t.shape().iter().map(|&s| s as usize).collect()).collect();
+    let plan = TblisEinsumPlan::<Complex<R>>::new_f(subscripts, &shapes, optimize, memory_limit, row_major)?;
+    unsafe { plan.execute_split_f(operands, out, cfg) }
+}
+
+impl<R> TblisEinsumPlan<Complex<R>>
+where
+    R: TblisFloatAPI + Float + Send + Sync + 'static,
+    Complex<R>: TblisFloatAPI,
+{
+    /// Execute einsum plan on split-complex tensors.
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if failed. Use [`TblisEinsumPlan::execute_split_f`] for fallible
+    ///   version.
+    ///
+    /// # Safety
+    ///
+    /// - This function does not check tensor data validity and mutability.
+    pub unsafe fn execute_split(
+        &self,
+        operands: &[&TblisSplitTensor<R>],
+        out: Option<&mut TblisSplitTensor<R>>,
+        cfg: Option<TblisEinsumCfg<Complex<R>>>,
+    ) -> Option<TblisSplitArray<R>> {
+        unsafe { self.execute_split_f(operands, out, cfg).unwrap() }
+    }
+
+    /// Execute einsum plan on split-complex tensors.
+    ///
+    /// # Safety
+    ///
+    /// - This function does not check tensor data validity and mutability.
+    ///
+    /// # See also
+    ///
+    /// [`TblisEinsumPlan::execute_split`] for non-fallible version.
+    pub unsafe fn execute_split_f(
+        &self,
+        operands: &[&TblisSplitTensor<R>],
+        out: Option<&mut TblisSplitTensor<R>>,
+        cfg: Option<TblisEinsumCfg<Complex<R>>>,
+    ) -> Result<Option<TblisSplitArray<R>>, TblisError> {
+        self.check_shapes(&operands.iter().map(|tsr| tsr.shape()).collect::<Vec<_>>())?;
+        let TblisEinsumCfg { alpha: alpha_out, beta: beta_out, allocator, memory_budget, check_overlap, .. } =
+            cfg.unwrap_or_default();
+        let allocator: Arc<dyn TblisAllocator<Complex<R>>> =
+            allocator.unwrap_or_else(|| Arc::new(TblisDefaultAllocator));
+        let allocator = TblisSplitAllocator(allocator);
+        // index slicing is not performed for split-complex tensors, so memory ceiling of the plan
+        // could not be honored
+        if let Some(slicing) = &self.slicing {
+            let mut plan = self.clone();
+            plan.slicing = None;
+            let required = plan.largest_intermediate() * size_of::<Complex<R>>();
+            return Err(TblisError::MemoryLimit { required, limit: slicing.max_bytes });
+        }
+        if let Some(budget) = memory_budget {
+            self.check_memory_budget_f(budget, out.is_none())?;
+        }
+        if let Some(out) = &out {
+            if out.shape() != self.shape_out {
+                let (expected, found) = (self.shape_out.clone(), out.shape().to_vec());
+                return Err(TblisError::OutputMismatch { expected, found });
+            }
+            if check_overlap {
+                check_split_overlap_f(out, operands)?;
+            }
+        }
+        // output allocated internally is uninitialized, beta is not applicable
+        let beta_out = if out.is_some() { beta_out } else { Complex::zero() };
+        let (one, zero) = (Complex::new(R::one(), R::zero()), Complex::zero());
+        let mut tensor_list: Vec<(TblisSplitTensor<R>, Option<TblisSplitArray<R>>)> = operands
+            .iter()
+            .zip(&self.shapes_broadcast)
+            .map(|(&t, shape)| {
+                let tsr = TblisSplitTensor { re: broadcast_tensor(&t.re, shape), im: broadcast_tensor(&t.im, shape) };
+                (tsr, None)
+            })
+            .collect();
+        #[cfg(feature = "debug_checks")]
+        let inputs_finite = {
+            use crate::debug_checks::inputs_finite;
+            let operands_re: Vec<&TblisTensor<R>> = tensor_list.iter().flat_map(|(t, _)| [&t.re, &t.im]).collect();
+            let out_re: Option<Vec<&TblisTensor<R>>> = out.as_deref().map(|t| vec![&t.re, &t.im]);
+            let operands_finite = inputs_finite(&operands_re, R::one(), R::zero(), None);
+            let out_finite = beta_out.is_zero() || out_re.is_none_or(|t| inputs_finite(&t, R::one(), R::zero(), None));
+            let scalars_finite = [alpha_out, beta_out].iter().all(|s| Float::is_finite(s.re) && Float::is_finite(s.im));
+            operands_finite && out_finite && scalars_finite
+        };
+        let num_steps = self.steps.len();
+        for (idx_step, step) in self.steps.iter().enumerate() {
+            let TblisPlanStep { contract, trace_a, trace_b, .. } = step;
+            let TblisContractStep { indices, idx_a, idx_b, idx_c, shape_c } = contract;
+            let is_last_step = idx_step == num_steps - 1;
+            let (alpha, beta) = if is_last_step { (alpha_out, beta_out) } else { (one, zero) };
+            let (arr_c, mut tsr_c) = match (is_last_step, &out) {
+                (true, Some(tsr_c)) => (None, (*tsr_c).clone()),
+                _ => {
+                    let mut arr_c = unsafe { TblisSplitArray::uninit_in_f(shape_c, self.row_major, &allocator)? };
+                    let tsr_c = arr_c.to_split_tensor_mut();
+                    (Some(arr_c), tsr_c)
+                },
+            };
+            let add_cfg = |alpha| {
+                TblisAddCfgBuilder::default().alpha(alpha).beta(beta).check_overlap(check_overlap).build().unwrap()
+            };
+
+            if let Some(idx_b) = idx_b {
+                let mut idx_a = idx_a.as_str();
+                let mut idx_b = idx_b.as_str();
+                for (trace, idx, i) in [(trace_a, &mut idx_a, indices[0]), (trace_b, &mut idx_b, indices[1])] {
+                    if let Some(trace) = trace {
+                        let mut arr_traced =
+                            unsafe { TblisSplitArray::uninit_in_f(&trace.shape, self.row_major, &allocator)? };
+                        let mut tsr_traced = arr_traced.to_split_tensor_mut();
+                        let cfg = TblisAddCfgBuilder::default().beta(zero).build().unwrap();
+                        let tsr_prev = &tensor_list[i].0;
+                        unsafe {
+                            split_tensor_add_in_f(
+                                tsr_prev,
+                                &trace.idx_prev,
+                                &mut tsr_traced,
+                                &trace.idx_traced,
+                                Some(cfg),
+                                &allocator,
+                            )?
+                        };
+                        tensor_list[i] = (tsr_traced, Some(arr_traced));
+                        *idx = &trace.idx_traced;
+                    }
+                }
+
+                let tsr_a = &tensor_list[indices[0]].0;
+                let tsr_b = &tensor_list[indices[1]].0;
+                match (idx_a.is_empty(), idx_b.is_empty()) {
+                    (false, false) => unsafe {
+                        let mult_cfg =
+                            TblisMultCfgBuilder::default().alpha(alpha).beta(beta).check_overlap(check_overlap).build();
+                        split_tensor_mult_in_f(
+                            tsr_a,
+                            idx_a,
+                            tsr_b,
+                            idx_b,
+                            &mut tsr_c,
+                            idx_c,
+                            Some(mult_cfg.unwrap()),
+                            &allocator,
+                        )?;
+                    },
+                    (true, true) => {
+                        let val_c = alpha * tsr_a.to_scalar()? * tsr_b.to_scalar()?;
+                        // uninitialized data should not be read when beta is zero
+                        let val_c = if beta.is_zero() { val_c } else { val_c + beta * tsr_c.to_scalar()? };
+                        unsafe { (*tsr_c.re.data, *tsr_c.im.data) = (val_c.re, val_c.im) };
+                    },
+                    (true, false) => {
+                        let cfg = add_cfg(alpha * tsr_a.to_scalar()?);
+                        unsafe { split_tensor_add_in_f(tsr_b, idx_b, &mut tsr_c, idx_c, Some(cfg), &allocator)? };
+                    },
+                    (false, true) => {
+                        let cfg = add_cfg(alpha * tsr_b.to_scalar()?);
+                        unsafe { split_tensor_add_in_f(tsr_a, idx_a, &mut tsr_c, idx_c, Some(cfg), &allocator)? };
+                    },
+                };
+            } else {
+                // case of tensor transpose (implement by add)
+                let tsr_a = &tensor_list[indices[0]].0;
+                unsafe { split_tensor_add_in_f(tsr_a, idx_a, &mut tsr_c, idx_c, Some(add_cfg(alpha)), &allocator)? };
+            }
+            #[cfg(feature = "debug_checks")]
+            if inputs_finite {
+                let context = || format!("output {idx_c:?} of einsum step {idx_step}");
+                crate::debug_checks::check_finite_f(&tsr_c.re, context)?;
+                crate::debug_checks::check_finite_f(&tsr_c.im, context)?;
+            }
+            tensor_list.push((tsr_c, arr_c));
+            // remove used tensors
+            let mut indices = indices.to_vec();
+            indices.sort_unstable_by(|a, b| b.cmp(a));
+            for i in indices {
+                tensor_list.remove(i);
+            }
+        }
+        assert!(tensor_list.len() == 1);
+        let (_, arr_opt) = tensor_list.pop().unwrap();
+        Ok(arr_opt)
+    }
+}
+
+/* #endregion */
+
+/* #region helpers */
+
+/// Parts of split-complex tensor, with the complex unit that each part is multiplied by
+/// (`1` for real part, `i` or `-i` for imaginary part).
+fn split_parts<R>(tsr: &TblisSplitTensor<R>, conj: bool) -> [(&TblisTensor<R>, Complex<R>); 2]
+where
+    R: TblisFloatAPI + Float,
+{
+    let unit_im = if conj { -R::one() } else { R::one() };
+    [(&tsr.re, Complex::new(R::one(), R::zero())), (&tsr.im, Complex::new(R::zero(), unit_im))]
+}
+
+/// Check that parts of output split-complex tensor are not self-overlapping, and do not overlap
+/// with input tensors.
+///
+/// Real and imaginary parts of the same tensor may be interleaved (views of complex tensor), so
+/// overlap between them is not checked.
+fn check_split_overlap_f<R>(out: &TblisSplitTensor<R>, inputs: &[&TblisSplitTensor<R>]) -> Result<(), TblisError>
+where
+    R: TblisFloatAPI + Float,
+{
+    for part in [&out.re, &out.im] {
+        check_overlap_f(part, &[])?;
+        if let Some(operand) = inputs.iter().position(|tsr| part.overlaps(&tsr.re) || part.overlaps(&tsr.im)) {
+            return Err(TblisError::Aliasing { operand });
+        }
+    }
+    Ok(())
+}
+
+/// Apply conjugation and imaginary part of $\beta$ to output tensor inplace.
+///
+/// Returns the real factors that are still to be applied to the real and imaginary parts.
+unsafe fn split_scale_beta_f<R>(
+    tsr: &mut TblisSplitTensor<R>,
+    idx: &str,
+    beta: Complex<R>,
+    conj: bool,
+    allocator: &dyn TblisAllocator<R>,
+) -> Result<[R; 2], TblisError>
+where
+    R: TblisFloatAPI + Float,
+{
+    let sign_im = if conj { -R::one() } else { R::one() };
+    if beta.im.is_zero() {
+        return Ok([beta.re, beta.re * sign_im]);
+    }
+    // (re + i im) * beta with conj of `im` folded in sign; `re` is copied since both parts are
+    // read while updated
+    let mut arr_re = unsafe { TblisArray::uninit_in_f(&tsr.re.shape, true, allocator)? };
+    let mut tsr_tmp = arr_re.to_tblis_tensor_mut();
+    let cfg = TblisAddCfgBuilder::default().beta(R::zero()).build().unwrap();
+    unsafe { tblis_tensor_add_f(&tsr.re, idx, &mut tsr_tmp, idx, Some(cfg))? };
+    // real and imaginary parts may be interleaved, so overlap is not checked
+    let cfg = TblisAddCfgBuilder::default().alpha(-beta.im * sign_im).beta(beta.re).check_overlap(false).build();
+    let cfg = cfg.unwrap();
+    unsafe { tblis_tensor_add_f(&tsr.im.clone(), idx, &mut tsr.re, idx, Some(cfg))? };
+    let cfg = TblisAddCfgBuilder::default().alpha(beta.im).beta(beta.re * sign_im).check_overlap(false).build();
+    let cfg = cfg.unwrap();
+    unsafe { tblis_tensor_add_f(&tsr_tmp, idx, &mut tsr.im, idx, Some(cfg))? };
+    drop(arr_re);
+    Ok([R::one(), R::one()])
+}
+
+/// Apply the remaining real factors of $\beta$ to parts that are not written.
+unsafe fn split_scale_remaining_f<R>(
+    tsr: &mut TblisSplitTensor<R>,
+    idx: &str,
+    beta_parts: [R; 2],
+) -> Result<(), TblisError>
+where
+    R: TblisFloatAPI + Float,
+{
+    for (part, beta) in [&mut tsr.re, &mut tsr.im].into_iter().zip(beta_parts) {
+        if beta.is_zero() {
+            // uninitialized data should not be read when beta is zero
+            let cfg = TblisSetCfgBuilder::default().check_overlap(false).build().unwrap();
+            unsafe { tblis_tensor_set_f(part, idx, R::zero(), Some(cfg))? };
+        } else if beta != R::one() {
+            let cfg = TblisScaleCfgBuilder::default().alpha(beta).check_overlap(false).build().unwrap();
+            unsafe { tblis_tensor_scale_f(part, idx, Some(cfg))? };
+        }
+    }
+    Ok(())
+}
+
+/* #endregion */
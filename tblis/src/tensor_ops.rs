@@ -4,7 +4,10 @@
 
 use crate::char_parse::char_parse;
 use crate::containers::*;
+use crate::error::TblisError;
 use crate::float_trait::*;
+use crate::views::layout_internal_overlap;
+use core::ops::Range;
 use core::ptr::null;
 use derive_builder::Builder;
 use std::collections::{BTreeMap, BTreeSet};
@@ -22,6 +25,9 @@ use tblis_ffi::tblis::{tblis_comm, tblis_config};
 /// - `beta`: The scalar multiplier $\beta$ for tensor $C$ (default: `0`).
 /// - `conja`: Whether to conjugate tensor $A$ (default: `false`).
 /// - `conjb`: Whether to conjugate tensor $B$ (default: `false`).
+/// - `check_overlap`: Whether to check that the output tensor is not self-overlapping and does not
+///   overlap with input tensors (default: `true`). Set to `false` only if overlapping is known to
+///   be harmless.
 ///
 /// # Usage
 ///
@@ -65,6 +71,8 @@ where
     pub conja: bool,
     #[builder(default = "false")]
     pub conjb: bool,
+    #[builder(default = "true")]
+    pub check_overlap: bool,
 }
 
 impl<T> Default for TblisTriCfg<T>
@@ -86,6 +94,9 @@ where
 /// - `beta`: The scalar multiplier $\beta$ for tensor $B$ (default: `1`).
 /// - `conja`: Whether to conjugate tensor $A$ (default: `false`).
 /// - `conjb`: Whether to conjugate tensor $B$ (default: `false`).
+/// - `check_overlap`: Whether to check that the output tensor is not self-overlapping and does not
+///   overlap with input tensors (default: `true`). Set to `false` only if overlapping is known to
+///   be harmless.
 ///
 /// # Usage
 ///
@@ -130,6 +141,8 @@ where
     pub conja: bool,
     #[builder(default = "false")]
     pub conjb: bool,
+    #[builder(default = "true")]
+    pub check_overlap: bool,
 }
 
 impl<T> Default for TblisBiCfg<T>
@@ -149,6 +162,9 @@ where
 /// - `cntx`: The TBLIS context (default: `null`).
 /// - `alpha`: The scalar multiplier $\alpha$ for tensor $A$ (default: `1`).
 /// - `conj`: Whether to conjugate tensor $A$ (default: `false`).
+/// - `check_overlap`: Whether to check that the output tensor is not self-overlapping and does not
+///   overlap with input tensors (default: `true`). Set to `false` only if overlapping is known to
+///   be harmless.
 ///
 /// # Usage
 ///
@@ -190,6 +206,8 @@ where
     pub alpha: T,
     #[builder(default = "false")]
     pub conj: bool,
+    #[builder(default = "true")]
+    pub check_overlap: bool,
 }
 
 impl<T> Default for TblisUniCfg<T>
@@ -207,6 +225,9 @@ where
 ///
 /// - `comm`: The communicator for parallel execution (default: `null`).
 /// - `cntx`: The TBLIS context (default: `null`).
+/// - `check_overlap`: Whether to check that the output tensor is not self-overlapping and does not
+///   overlap with input tensors (default: `true`). Set to `false` only if overlapping is known to
+///   be harmless.
 ///
 /// # Alias
 ///
@@ -225,6 +246,8 @@ pub struct TblisZeroCfg {
     pub comm: *const tblis_comm,
     #[builder(default = "null()")]
     pub cntx: *const tblis_config,
+    #[builder(default = "true")]
+    pub check_overlap: bool,
 }
 
 impl Default for TblisZeroCfg {
@@ -238,34 +261,55 @@ impl Default for TblisZeroCfg {
 /* #region validity check */
 
 /// Check the validity of subscripts and shapes, and return a size dictionary.
-fn check_size_dict(subscripts: &[&str], shapes: &[&[isize]]) -> Result<BTreeMap<char, isize>, String> {
+fn check_size_dict(subscripts: &[&str], shapes: &[&[isize]]) -> Result<BTreeMap<char, isize>, TblisError> {
     let mut size_dict = BTreeMap::new();
     if subscripts.len() != shapes.len() {
-        return Err(format!("Number of subscripts and shapes do not match: {} vs {}", subscripts.len(), shapes.len()));
+        return Err(TblisError::OperandCount { expected: subscripts.len(), found: shapes.len() });
     }
-    for (subscript, shape) in subscripts.iter().zip(shapes.iter()) {
-        let subscript = subscript.chars().collect::<Vec<char>>();
-        if subscript.len() != shape.len() {
-            return Err(format!("Subscript length and shape length do not match: {subscript:?} vs {shape:?}"));
+    for (&subscript, shape) in subscripts.iter().zip(shapes.iter()) {
+        if subscript.chars().count() != shape.len() {
+            return Err(TblisError::SubscriptLength { subscript: subscript.to_string(), shape: shape.to_vec() });
         }
-        for (c, &s) in subscript.iter().zip(shape.iter()) {
+        for (c, &s) in subscript.chars().zip(shape.iter()) {
             if s < 0 {
-                return Err(format!("Invalid dimension size {s} for index {c} in subscript {subscript:?}"));
+                return Err(TblisError::InvalidDimension { index: c, size: s, subscript: subscript.to_string() });
             }
-            if let Some(&existing) = size_dict.get(c) {
+            if let Some(&existing) = size_dict.get(&c) {
                 if existing != s {
-                    return Err(format!(
-                        "Inconsistent dimension size for index {c}: {existing} vs {s} in subscript {subscript:?}"
-                    ));
+                    let subscript = subscript.to_string();
+                    return Err(TblisError::ShapeMismatch { index: c, expected: existing, found: s, subscript });
                 }
             } else {
-                size_dict.insert(*c, s);
+                size_dict.insert(c, s);
             }
         }
     }
     Ok(size_dict)
 }
 
+/// Check that output tensor is not self-overlapping, and does not overlap with input tensors.
+///
+/// Only provable overlap is reported as error; layouts too large to be decided are allowed.
+pub(crate) fn check_overlap_f<T>(out: &TblisTensor<T>, inputs: &[&TblisTensor<T>]) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    if layout_internal_overlap(&out.shape, &out.stride) == Some(true) {
+        return Err(TblisError::SelfOverlap { shape: out.shape.clone(), stride: out.stride.clone() });
+    }
+    match inputs.iter().position(|input| out.overlaps(input)) {
+        Some(operand) => Err(TblisError::Aliasing { operand }),
+        None => Ok(()),
+    }
+}
+
+/// Build [`TblisError::UniqueIndex`] from subscripts and their unique indices.
+fn unique_index_error(func: &'static str, subscripts: &[&str], unique: &[&BTreeSet<char>]) -> TblisError {
+    let subscripts = subscripts.iter().map(|s| s.to_string()).collect();
+    let unique = unique.iter().map(|u| u.iter().collect()).collect();
+    TblisError::UniqueIndex { func, subscripts, unique }
+}
+
 /* #endregion */
 
 /* #region add */
@@ -294,11 +338,13 @@ pub use TblisBiCfgBuilder as TblisAddCfgBuilder;
 ///   - `beta`: The scalar multiplier $\beta$ for tensor $B$ (default: `1`).
 ///   - `conja`: Whether to conjugate tensor $A$ (default: `false`).
 ///   - `conjb`: Whether to conjugate tensor $B$ (default: `false`).
+///   - `check_overlap`: Whether to check overlapping of output and input tensors (default: `true`).
 ///
 /// # Panics
 ///
 /// - If the indices and shapes of the tensors are incompatible.
 /// - If both tensors have unique indices (i.e., indices that do not appear in the other tensor).
+/// - If $B$ is self-overlapping or overlaps with $A$ (unless `check_overlap` is `false`).
 ///
 /// # Safety
 ///
@@ -334,7 +380,7 @@ pub unsafe fn tblis_tensor_add_f<T>(
     b: &mut TblisTensor<T>,
     idx_b: &str,
     cfg: Option<TblisAddCfg<T>>,
-) -> Result<(), String>
+) -> Result<(), TblisError>
 where
     T: TblisFloatAPI,
 {
@@ -345,16 +391,20 @@ where
     let chk_a_only = &chk_a - &chk_ab;
     let chk_b_only = &chk_b - &chk_ab;
     if !(chk_a_only.is_empty() || chk_b_only.is_empty()) {
-        return Err(format!(
-            "tblis_tensor_add: Only one of two tensors can have unique indices. Unique to a ({idx_a}): {chk_a_only:?}, unique to b ({idx_b}): {chk_b_only:?}"
-        ));
+        // only one of two tensors can have unique indices
+        return Err(unique_index_error("tblis_tensor_add", &[idx_a, idx_b], &[&chk_a_only, &chk_b_only]));
     }
 
     let indices = char_parse(&[idx_a, idx_b])?;
     let (a_idx, b_idx) = (indices[0].as_ptr(), indices[1].as_ptr());
-    let TblisAddCfg { comm, cntx, alpha, beta, conja, conjb } = cfg.unwrap_or_default();
+    let TblisAddCfg { comm, cntx, alpha, beta, conja, conjb, check_overlap } = cfg.unwrap_or_default();
+    if check_overlap {
+        check_overlap_f(b, &[a])?;
+    }
 
+    // scalar and conjugation are set on local copies, so that those of caller are kept
     let mut a = a.clone();
+    let mut b = b.clone();
 
     a.scalar = alpha;
     b.scalar = beta;
@@ -428,7 +478,7 @@ pub unsafe fn tblis_tensor_dot_f<T>(
     b: &TblisTensor<T>,
     idx_b: &str,
     cfg: Option<TblisDotCfg<T>>,
-) -> Result<T, String>
+) -> Result<T, TblisError>
 where
     T: TblisFloatAPI,
 {
@@ -438,20 +488,13 @@ where
     let chk_ab = &chk_a & &chk_b;
     let chk_a_only = &chk_a - &chk_ab;
     let chk_b_only = &chk_b - &chk_ab;
-    if !chk_a_only.is_empty() {
-        return Err(format!(
-            "tblis_tensor_dot: Unique indices is not allowed. Unique to a ({idx_a}) of b ({idx_b}): {chk_a_only:?}"
-        ));
-    }
-    if !chk_b_only.is_empty() {
-        return Err(format!(
-            "tblis_tensor_dot: Unique indices is not allowed. Unique to b ({idx_b}) of a ({idx_a}): {chk_b_only:?}"
-        ));
+    if !chk_a_only.is_empty() || !chk_b_only.is_empty() {
+        return Err(unique_index_error("tblis_tensor_dot", &[idx_a, idx_b], &[&chk_a_only, &chk_b_only]));
     }
 
     let indices = char_parse(&[idx_a, idx_b])?;
     let (a_idx, b_idx) = (indices[0].as_ptr(), indices[1].as_ptr());
-    let TblisDotCfg { comm, cntx, alpha, beta, conja, conjb } = cfg.unwrap_or_default();
+    let TblisDotCfg { comm, cntx, alpha, beta, conja, conjb, .. } = cfg.unwrap_or_default();
 
     let mut a = a.clone();
     let mut b = b.clone();
@@ -461,7 +504,7 @@ where
     a.conj = conja;
     b.conj = conjb;
 
-    let result = T::zero();
+    let mut result = T::zero().to_ffi_scalar();
     unsafe {
         tblis_ffi::tblis::tblis_tensor_dot(
             comm,
@@ -470,10 +513,10 @@ where
             a_idx,
             &b.to_ffi_tensor(),
             b_idx,
-            &mut result.to_ffi_scalar(),
+            &mut result,
         );
     }
-    Ok(result)
+    Ok(T::from_ffi_scalar(&result))
 }
 
 /* #endregion */
@@ -503,12 +546,14 @@ pub use TblisTriCfgBuilder as TblisMultCfgBuilder;
 ///   - `conja`: Whether to conjugate tensor $A$ (default: `false`).
 ///   - `conjb`: Whether to conjugate tensor $B$ (default: `false`).
 ///   - `conjc`: Whether to conjugate tensor $C$ (default: `false`).
+///   - `check_overlap`: Whether to check overlapping of output and input tensors (default: `true`).
 ///
 /// # Panics
 ///
 /// - If the indices and shapes of the tensors are incompatible.
 /// - If either tensors have unique indices (i.e., indices that do not appear in the other two
 ///   tensors).
+/// - If $C$ is self-overlapping or overlaps with $A$ or $B$ (unless `check_overlap` is `false`).
 ///
 /// # Safety
 ///
@@ -548,7 +593,7 @@ pub unsafe fn tblis_tensor_mult_f<T>(
     c: &mut TblisTensor<T>,
     idx_c: &str,
     cfg: Option<TblisMultCfg<T>>,
-) -> Result<(), String>
+) -> Result<(), TblisError>
 where
     T: TblisFloatAPI,
 {
@@ -563,17 +608,21 @@ where
     let chk_b_only = &chk_b - &(&chk_ab | &chk_bc);
     let chk_c_only = &chk_c - &(&chk_ac | &chk_bc);
     if !chk_a_only.is_empty() || !chk_b_only.is_empty() || !chk_c_only.is_empty() {
-        return Err(format!(
-            "tblis_tensor_mult: Unique indices is not allowed. Input and unique indices: a ({idx_a}): {chk_a_only:?}, b ({idx_b}): {chk_b_only:?}, c ({idx_c}): {chk_c_only:?}"
-        ));
+        let (subscripts, unique) = ([idx_a, idx_b, idx_c], [&chk_a_only, &chk_b_only, &chk_c_only]);
+        return Err(unique_index_error("tblis_tensor_mult", &subscripts, &unique));
     }
 
     let indices = char_parse(&[idx_a, idx_b, idx_c])?;
     let (a_idx, b_idx, c_idx) = (indices[0].as_ptr(), indices[1].as_ptr(), indices[2].as_ptr());
-    let TblisMultCfg { comm, cntx, alpha, beta, conja, conjb } = cfg.unwrap_or_default();
+    let TblisMultCfg { comm, cntx, alpha, beta, conja, conjb, check_overlap } = cfg.unwrap_or_default();
+    if check_overlap {
+        check_overlap_f(c, &[a, b])?;
+    }
 
+    // scalar and conjugation are set on local copies, so that those of caller are kept
     let mut a = a.clone();
     let mut b = b.clone();
+    let mut c = c.clone();
 
     a.scalar = alpha;
     b.scalar = T::one();
@@ -678,8 +727,10 @@ pub use TblisUniCfgBuilder as TblisReduceCfgBuilder;
 /// - `a`: The tensor $A$.
 /// - `idx_a`: The indices of tensor $A$ to be reduced. All indices in `idx_a` will be reduced.
 /// - `op`: The reduction operation to be performed.
-///   - Allowed values include: `Sum`, `SumAbs` (or `Norm1`), `Max` (or `NormInf`), `MaxAbs`, `Min`,
+///   - Allowed values include: `Sum`, `SumAbs` (or `Norm1`), `Max`, `MaxAbs` (or `NormInf`), `Min`,
 ///     `MinAbs`, and `Norm2`.
+///   - `Max` and `Min` are not defined for complex types (error). For norms of complex tensors,
+///     [`tblis_tensor_norm`] returns a real value.
 /// - `cfg`: Optional configuration for the reduction operation.
 ///   - `comm`: The communicator for parallel execution (default: `null`).
 ///   - `cntx`: The TBLIS context (default: `null`).
@@ -689,6 +740,7 @@ pub use TblisUniCfgBuilder as TblisReduceCfgBuilder;
 /// # Panics
 ///
 /// - If the indices and shapes of the tensor are incompatible.
+/// - If `op` is `Max` or `Min` for complex types.
 ///
 /// # Safety
 ///
@@ -719,35 +771,376 @@ pub unsafe fn tblis_tensor_reduce_f<T>(
     idx_a: &str,
     op: TblisReduceOp,
     cfg: Option<TblisReduceCfg<T>>,
-) -> Result<T, String>
+) -> Result<T, TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_reduce_raw(a, idx_a, op, cfg).map(|(value, _)| value) }
+}
+
+/// Reduction by TBLIS, returns both the value and offset of the result element.
+unsafe fn tblis_tensor_reduce_raw<T>(
+    a: &TblisTensor<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> Result<(T, isize), TblisError>
 where
     T: TblisFloatAPI,
 {
-    check_size_dict(&[idx_a], &[&a.shape]).unwrap();
+    if T::IS_COMPLEX && matches!(op, TblisReduceOp::Max | TblisReduceOp::Min) {
+        return Err(TblisError::UnsupportedReduction { op, msg: "ordering is not defined for complex".to_string() });
+    }
+    check_size_dict(&[idx_a], &[&a.shape])?;
 
     let indices = char_parse(&[idx_a])?;
     let a_idx = indices[0].as_ptr();
-    let TblisReduceCfg { comm, cntx, alpha, conj } = cfg.unwrap_or_default();
+    let TblisReduceCfg { comm, cntx, alpha, conj, .. } = cfg.unwrap_or_default();
 
     let mut a = a.clone();
     a.scalar = alpha;
     a.conj = conj;
     let op = op.into();
 
-    let result = T::zero();
+    let mut result = T::zero().to_ffi_scalar();
     let mut idx = 0_isize;
     unsafe {
-        tblis_ffi::tblis::tblis_tensor_reduce(
-            comm,
-            cntx,
-            op,
-            &a.to_ffi_tensor(),
-            a_idx,
-            &mut result.to_ffi_scalar(),
-            &mut idx,
-        );
+        tblis_ffi::tblis::tblis_tensor_reduce(comm, cntx, op, &a.to_ffi_tensor(), a_idx, &mut result, &mut idx);
+    }
+    Ok((T::from_ffi_scalar(&result), idx))
+}
+
+/// Kind of tensor norm for [`tblis_tensor_norm`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TblisNormKind {
+    /// Sum of absolute values $\sum |a|$.
+    Norm1,
+    /// Frobenius norm $\sqrt{\sum |a|^2}$.
+    Norm2,
+    /// Maximum absolute value $\max |a|$.
+    NormInf,
+}
+
+impl From<TblisNormKind> for TblisReduceOp {
+    fn from(kind: TblisNormKind) -> Self {
+        match kind {
+            TblisNormKind::Norm1 => TblisReduceOp::SumAbs,
+            TblisNormKind::Norm2 => TblisReduceOp::Norm2,
+            TblisNormKind::NormInf => TblisReduceOp::MaxAbs,
+        }
+    }
+}
+
+/// TBLIS tensor norm $\Vert \alpha A \Vert$, returned as real value.
+///
+/// This is [`tblis_tensor_reduce`] with `SumAbs`, `Norm2` or `MaxAbs`, but returns
+/// [`ComplexFloat::Real`](num::complex::ComplexFloat::Real) instead of `T`, which is more
+/// appropriate for complex tensors.
+///
+/// # Parameters
+///
+/// - `a`: The tensor $A$.
+/// - `idx_a`: The indices of tensor $A$. All indices in `idx_a` will be reduced.
+/// - `kind`: The kind of norm.
+/// - `cfg`: Optional configuration (see [`tblis_tensor_reduce`]). The result is scaled by
+///   $|\alpha|$; `conj` does not affect the result.
+///
+/// # Panics
+///
+/// - If the indices and shapes of the tensor are incompatible.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_tensor_norm_f`] for fallible version.
+pub unsafe fn tblis_tensor_norm<T>(
+    a: &TblisTensor<T>,
+    idx_a: &str,
+    kind: TblisNormKind,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> T::Real
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_norm_f(a, idx_a, kind, cfg).unwrap() }
+}
+
+/// TBLIS tensor norm $\Vert \alpha A \Vert$, returned as real value.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+pub unsafe fn tblis_tensor_norm_f<T>(
+    a: &TblisTensor<T>,
+    idx_a: &str,
+    kind: TblisNormKind,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> Result<T::Real, TblisError>
+where
+    T: TblisFloatAPI,
+{
+    let mut cfg = cfg.unwrap_or_default();
+    let alpha = cfg.alpha;
+    cfg.alpha = T::one();
+    let (value, _) = unsafe { tblis_tensor_reduce_raw(a, idx_a, kind.into(), Some(cfg))? };
+    // norm is real by definition; imaginary part (if any) is discarded
+    Ok(value.re() * alpha.abs())
+}
+
+/// TBLIS tensor reduction $\gamma = \alpha \mathrm{op}(A)$, with location of the result element.
+///
+/// This function is only valid for `Max`, `MaxAbs`, `Min` and `MinAbs`.
+///
+/// # Returns
+///
+/// - `value`: The reduction result $\gamma$.
+/// - `offset`: Flat offset (in elements, relative to data pointer of $A$) of the result element.
+/// - `index`: Multi-index of the result element, decoded from `offset` by shape and stride of $A$.
+///
+/// # Panics
+///
+/// - If the indices and shapes of the tensor are incompatible.
+/// - If the operation is not one of `Max`, `MaxAbs`, `Min`, `MinAbs`, or the tensor is empty.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_tensor_reduce_with_index_f`] for fallible version.
+pub unsafe fn tblis_tensor_reduce_with_index<T>(
+    a: &TblisTensor<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> (T, isize, Vec<isize>)
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_reduce_with_index_f(a, idx_a, op, cfg).unwrap() }
+}
+
+/// TBLIS tensor reduction $\gamma = \alpha \mathrm{op}(A)$, with location of the result element.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+pub unsafe fn tblis_tensor_reduce_with_index_f<T>(
+    a: &TblisTensor<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> Result<(T, isize, Vec<isize>), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    use TblisReduceOp::*;
+    if !matches!(op, Max | MaxAbs | Min | MinAbs) {
+        return Err(TblisError::UnsupportedReduction { op, msg: "location is only defined for max/min".to_string() });
+    }
+    if a.shape.contains(&0) {
+        return Err(TblisError::UnsupportedReduction { op, msg: "tensor is empty".to_string() });
+    }
+    let (value, offset) = unsafe { tblis_tensor_reduce_raw(a, idx_a, op, cfg)? };
+    let index = offset_to_index(offset, &a.shape, &a.stride).ok_or_else(|| TblisError::UnsupportedReduction {
+        op,
+        msg: format!("offset {offset} can not be decoded by shape {:?} and stride {:?}", a.shape, a.stride),
+    })?;
+    Ok((value, offset, index))
+}
+
+/// Decode flat offset to multi-index by shape and stride.
+///
+/// Returns `None` if the offset does not correspond to any element (or the layout is
+/// self-overlapping, where the decoded index is not unique).
+fn offset_to_index(offset: isize, shape: &[isize], stride: &[isize]) -> Option<Vec<isize>> {
+    // flip negative strides, so that all strides are non-negative
+    let shift = shape.iter().zip(stride).filter(|(_, &t)| t < 0).map(|(&s, &t)| (s - 1) * t).sum::<isize>();
+    let mut rem = offset - shift;
+    let mut order = (0..shape.len()).collect::<Vec<usize>>();
+    order.sort_by_key(|&i| -stride[i].abs());
+    let mut index = vec![0; shape.len()];
+    for i in order {
+        let t = stride[i].abs();
+        if t == 0 || shape[i] == 1 {
+            continue;
+        }
+        let k = (rem / t).min(shape[i] - 1);
+        rem -= k * t;
+        index[i] = if stride[i] < 0 { shape[i] - 1 - k } else { k };
+    }
+    let decoded = index.iter().zip(stride).map(|(&i, &t)| i * t).sum::<isize>();
+    (rem == 0 && decoded == offset).then_some(index)
+}
+
+/// TBLIS partial tensor reduction $B_{i} = \alpha \mathrm{op}_{j}(A_{ij})$.
+///
+/// Indices of `idx_a` that do not appear in `idx_b` are reduced; the result for each element of
+/// $B$ is written to `b` (previous values of `b` are overwritten).
+///
+/// # Parameters
+///
+/// - `a`: The tensor $A$.
+/// - `idx_a`: The indices of tensor $A$.
+/// - `op`: The reduction operation to be performed (see [`tblis_tensor_reduce`]).
+/// - `b`: The output tensor $B$ (will be modified in place).
+/// - `idx_b`: The indices of tensor $B$. All indices must also appear in `idx_a`.
+/// - `cfg`: Optional configuration for the reduction operation (see [`tblis_tensor_reduce`]).
+///
+/// # Notes
+///
+/// `Sum` is performed by [`tblis_tensor_add`] in one call. Other operations are performed by
+/// [`tblis_tensor_reduce`] on each slice of $A$, i.e. one FFI call per element of $B$:
+/// - if $B$ has fewer elements than TBLIS threads ([`tblis_get_num_threads`]), or `comm` is given,
+///   slices are reduced one by one, each by all TBLIS threads;
+/// - otherwise, elements of $B$ are split into contiguous chunks that are reduced in parallel (one
+///   scoped thread per TBLIS thread, each calling TBLIS with one thread).
+///
+/// The cost per element of $B$ is still a TBLIS call, so this function is efficient when slices of
+/// $A$ are not too small (e.g. `"ijk->i"` with large `jk`); for many tiny slices, consider
+/// transposing $A$ so that reduced indices are contiguous, or reducing in user code.
+///
+/// [`tblis_get_num_threads`]: crate::threading::tblis_get_num_threads
+///
+/// # Panics
+///
+/// - If the indices and shapes of the tensors are incompatible.
+/// - If `idx_b` has indices that are not in `idx_a`.
+/// - If $B$ is self-overlapping or overlaps with $A$ (unless `check_overlap` is `false`).
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+///
+/// # See also
+///
+/// - [`tblis_tensor_reduce_partial_f`] for fallible version.
+pub unsafe fn tblis_tensor_reduce_partial<T>(
+    a: &TblisTensor<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    b: &mut TblisTensor<T>,
+    idx_b: &str,
+    cfg: Option<TblisReduceCfg<T>>,
+) where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_reduce_partial_f(a, idx_a, op, b, idx_b, cfg).unwrap() }
+}
+
+/// TBLIS partial tensor reduction $B_{i} = \alpha \mathrm{op}_{j}(A_{ij})$.
+///
+/// # Safety
+///
+/// - This function does not check tensor data validity and mutability.
+pub unsafe fn tblis_tensor_reduce_partial_f<T>(
+    a: &TblisTensor<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    b: &mut TblisTensor<T>,
+    idx_b: &str,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    check_size_dict(&[idx_a, idx_b], &[&a.shape, &b.shape])?;
+    let chk_a = idx_a.chars().collect::<BTreeSet<char>>();
+    let chk_b = idx_b.chars().collect::<BTreeSet<char>>();
+    let chk_b_only = &chk_b - &chk_a;
+    if !chk_b_only.is_empty() {
+        let empty = BTreeSet::new();
+        return Err(unique_index_error("tblis_tensor_reduce_partial", &[idx_a, idx_b], &[&empty, &chk_b_only]));
+    }
+    let cfg = cfg.unwrap_or_default();
+    if cfg.check_overlap {
+        check_overlap_f(b, &[a])?;
+    }
+
+    if op == TblisReduceOp::Sum {
+        let TblisReduceCfg { comm, cntx, alpha, conj, check_overlap } = cfg;
+        let cfg = TblisAddCfg { comm, cntx, alpha, beta: T::zero(), conja: conj, conjb: false, check_overlap };
+        return unsafe { tblis_tensor_add_f(a, idx_a, b, idx_b, Some(cfg)) };
+    }
+
+    // dimensions of `a` that are kept (not reduced)
+    let idx_a_chars = idx_a.chars().collect::<Vec<char>>();
+    let idx_b_chars = idx_b.chars().collect::<Vec<char>>();
+    let idx_reduced = idx_a_chars.iter().filter(|c| !chk_b.contains(c)).collect::<String>();
+    let dims_reduced = (0..idx_a_chars.len()).filter(|&i| !chk_b.contains(&idx_a_chars[i])).collect::<Vec<usize>>();
+    let mut a_slice = a.clone();
+    a_slice.shape = dims_reduced.iter().map(|&i| a.shape[i]).collect();
+    a_slice.stride = dims_reduced.iter().map(|&i| a.stride[i]).collect();
+
+    // strides of `a` along each index of `b` (summed over repeated indices of `a`)
+    let stride_kept = idx_b_chars
+        .iter()
+        .map(|c| idx_a_chars.iter().zip(&a.stride).filter(|(x, _)| *x == c).map(|(_, &t)| t).sum::<isize>())
+        .collect::<Vec<isize>>();
+
+    if b.shape.contains(&0) {
+        return Ok(());
+    }
+    let size_b = b.shape.iter().product::<isize>() as usize;
+    let (data_a, data_b, shape_b, stride_b) = (a.data, b.data, &b.shape, &b.stride);
+    // reduce slices of `a` for elements of `b` in range of flat (row-major) index
+    let reduce_range = |range: Range<usize>| -> Result<(), TblisError> {
+        let mut a_slice = a_slice.clone();
+        for n in range {
+            let (mut rem, mut offset_a, mut offset_b) = (n as isize, 0, 0);
+            for k in (0..shape_b.len()).rev() {
+                let i = rem % shape_b[k];
+                rem /= shape_b[k];
+                (offset_a, offset_b) = (offset_a + i * stride_kept[k], offset_b + i * stride_b[k]);
+            }
+            a_slice.data = unsafe { data_a.offset(offset_a) };
+            let value = unsafe { tblis_tensor_reduce_f(&a_slice, &idx_reduced, op, Some(cfg.clone()))? };
+            unsafe { *data_b.offset(offset_b) = value };
+        }
+        Ok(())
+    };
+
+    let num_threads = crate::threading::tblis_get_num_threads();
+    if !cfg.comm.is_null() || num_threads <= 1 || size_b < num_threads {
+        // few elements of `b`: each slice is reduced by all TBLIS threads
+        return reduce_range(0..size_b);
+    }
+    // many elements of `b`: chunks of elements are reduced in parallel, each by one TBLIS thread
+    let reduce_range = AssertSend(reduce_range);
+    let chunk = size_b.div_ceil(num_threads);
+    std::thread::scope(|s| {
+        let handles = (0..size_b)
+            .step_by(chunk)
+            .map(|start| {
+                let reduce_range = &reduce_range;
+                s.spawn(move || {
+                    crate::threading::tblis_set_num_threads(1);
+                    (reduce_range.get())(start..(start + chunk).min(size_b))
+                })
+            })
+            .collect::<Vec<_>>();
+        handles.into_iter().try_for_each(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+    })
+}
+
+/// (dev-only) Wrapper to share raw pointers of tensors with scoped threads.
+///
+/// Threads must write to disjoint elements.
+struct AssertSend<T>(T);
+
+unsafe impl<T> Send for AssertSend<T> {}
+unsafe impl<T> Sync for AssertSend<T> {}
+
+impl<T> AssertSend<T> {
+    /// Access by method, so that closures capture the wrapper instead of its field.
+    fn get(&self) -> &T {
+        &self.0
     }
-    Ok(result)
 }
 
 /* #endregion */
@@ -768,6 +1161,7 @@ pub use TblisUniCfgBuilder as TblisScaleCfgBuilder;
 ///   - `cntx`: The TBLIS context (default: `null`).
 ///   - `alpha`: The scalar multiplier $\alpha$ for tensor $A$ (default: `1`).
 ///   - `conj`: Whether to conjugate tensor $A$ (default: `false`).
+///   - `check_overlap`: Whether to check that $A$ is not self-overlapping (default: `true`).
 ///
 /// # Panics
 ///
@@ -800,16 +1194,21 @@ pub unsafe fn tblis_tensor_scale_f<T>(
     a: &mut TblisTensor<T>,
     idx_a: &str,
     cfg: Option<TblisScaleCfg<T>>,
-) -> Result<(), String>
+) -> Result<(), TblisError>
 where
     T: TblisFloatAPI,
 {
-    check_size_dict(&[idx_a], &[&a.shape]).unwrap();
+    check_size_dict(&[idx_a], &[&a.shape])?;
 
     let indices = char_parse(&[idx_a])?;
     let a_idx = indices[0].as_ptr();
-    let TblisScaleCfg { comm, cntx, alpha, conj } = cfg.unwrap_or_default();
+    let TblisScaleCfg { comm, cntx, alpha, conj, check_overlap } = cfg.unwrap_or_default();
+    if check_overlap {
+        check_overlap_f(a, &[])?;
+    }
 
+    // scalar and conjugation are set on local copy, so that those of caller are kept
+    let mut a = a.clone();
     a.scalar = alpha;
     a.conj = conj;
 
@@ -836,6 +1235,7 @@ pub use TblisZeroCfgBuilder as TblisSetCfgBuilder;
 /// - `cfg`: Optional configuration for the operation.
 ///   - `comm`: The communicator for parallel execution (default: `null`).
 ///   - `cntx`: The TBLIS context (default: `null`).
+///   - `check_overlap`: Whether to check that $A$ is not self-overlapping (default: `true`).
 ///
 /// # Safety
 ///
@@ -865,15 +1265,18 @@ pub unsafe fn tblis_tensor_set_f<T>(
     idx_a: &str,
     alpha: T,
     cfg: Option<TblisSetCfg>,
-) -> Result<(), String>
+) -> Result<(), TblisError>
 where
     T: TblisFloatAPI,
 {
-    check_size_dict(&[idx_a], &[&a.shape]).unwrap();
+    check_size_dict(&[idx_a], &[&a.shape])?;
 
     let indices = char_parse(&[idx_a])?;
     let a_idx = indices[0].as_ptr();
-    let TblisSetCfg { comm, cntx } = cfg.unwrap_or_default();
+    let TblisSetCfg { comm, cntx, check_overlap } = cfg.unwrap_or_default();
+    if check_overlap {
+        check_overlap_f(a, &[])?;
+    }
 
     unsafe {
         tblis_ffi::tblis::tblis_tensor_set(comm, cntx, &alpha.to_ffi_scalar(), &mut a.to_ffi_tensor(), a_idx);
@@ -903,6 +1306,7 @@ pub use TblisUniCfgBuilder as TblisShiftCfgBuilder;
 ///   - `alpha`: The scalar multiplier $\beta$ for tensor $A$ (default: `1`) **NOTE that this is
 ///     BETA, not ALPHA in equation**.
 ///   - `conj`: Whether to conjugate tensor $A$ (default: `false`).
+///   - `check_overlap`: Whether to check that $A$ is not self-overlapping (default: `true`).
 ///
 /// # Panics
 ///
@@ -936,16 +1340,21 @@ pub unsafe fn tblis_tensor_shift_f<T>(
     idx_a: &str,
     alpha: T,
     cfg: Option<TblisShiftCfg<T>>,
-) -> Result<(), String>
+) -> Result<(), TblisError>
 where
     T: TblisFloatAPI,
 {
-    check_size_dict(&[idx_a], &[&a.shape]).unwrap();
+    check_size_dict(&[idx_a], &[&a.shape])?;
 
     let indices = char_parse(&[idx_a])?;
     let a_idx = indices[0].as_ptr();
-    let TblisShiftCfg { comm, cntx, alpha: alpha_a, conj } = cfg.unwrap_or_default();
+    let TblisShiftCfg { comm, cntx, alpha: alpha_a, conj, check_overlap } = cfg.unwrap_or_default();
+    if check_overlap {
+        check_overlap_f(a, &[])?;
+    }
 
+    // scalar and conjugation are set on local copy, so that those of caller are kept
+    let mut a = a.clone();
     a.scalar = alpha_a;
     a.conj = conj;
 
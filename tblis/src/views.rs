@@ -0,0 +1,664 @@
+//! Borrow-checked tensor views and safe operations on them.
+//!
+//! [`TblisTensor`] only holds a raw pointer, so that all operations on it are `unsafe`. Views in
+//! this module are created from slices, with shape and stride validated against the slice length.
+//! Operations taking views are safe to call:
+//! - input tensors are shared borrows ([`TblisView`]);
+//! - output tensors are unique borrows ([`TblisViewMut`]), and their layouts are checked to be not
+//!   self-overlapping.
+
+use crate::einsum_impl::shape_to_stride;
+use crate::prelude::*;
+use core::marker::PhantomData;
+use opt_einsum_path::typing::SizeLimitType;
+use opt_einsum_path::PathOptimizer;
+
+/* #region layout check */
+
+/// Offsets of a layout overflow `isize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LayoutOverflow;
+
+/// Minimum and maximum offset (in elements) of a layout.
+///
+/// Returns `Ok(None)` if the layout contains no element (some dimension is zero), and
+/// [`LayoutOverflow`] if offsets are not representable by `isize`.
+pub(crate) fn layout_extent(shape: &[isize], stride: &[isize]) -> Result<Option<(isize, isize)>, LayoutOverflow> {
+    if shape.contains(&0) {
+        return Ok(None);
+    }
+    let (mut min, mut max) = (0_isize, 0_isize);
+    for (&s, &t) in shape.iter().zip(stride.iter()) {
+        let offset = (s - 1).checked_mul(t).ok_or(LayoutOverflow)?;
+        match t >= 0 {
+            true => max = max.checked_add(offset).ok_or(LayoutOverflow)?,
+            false => min = min.checked_add(offset).ok_or(LayoutOverflow)?,
+        }
+    }
+    Ok(Some((min, max)))
+}
+
+/// Whether different indices of a layout may refer to the same memory location.
+///
+/// This check is conservative: some exotic interleaved layouts that are not overlapping may also be
+/// considered as self-overlapping.
+pub(crate) fn layout_self_overlapping(shape: &[isize], stride: &[isize]) -> bool {
+    if shape.contains(&0) {
+        return false;
+    }
+    let mut dims = shape
+        .iter()
+        .zip(stride.iter())
+        .filter(|(&s, _)| s > 1)
+        .map(|(&s, &t)| (s as usize, t.unsigned_abs()))
+        .collect::<Vec<_>>();
+    dims.sort_unstable_by_key(|&(_, t)| t);
+    // saturated extent is larger than any stride, so that the following dimensions overlap
+    let mut extent = 0_usize;
+    for (s, t) in dims {
+        if t <= extent {
+            return true;
+        }
+        extent = extent.saturating_add((s - 1).saturating_mul(t));
+    }
+    false
+}
+
+/// Maximum number of search steps of [`bounded_solvable`]; beyond it the answer is undecided.
+const OVERLAP_MAX_WORK: usize = 1 << 16;
+
+/// Whether $\sum_k c_k x_k = r$ has an integer solution with $0 \leq x_k \leq u_k$, for terms
+/// $(c_k, u_k)$ with positive coefficients $c_k$.
+///
+/// Returns `None` if not decided within [`OVERLAP_MAX_WORK`] search steps.
+fn bounded_solvable(terms: &[(i128, i128)], rhs: i128) -> Option<bool> {
+    // terms of the same coefficient are merged, larger coefficients are searched first
+    let mut merged: Vec<(i128, i128)> = vec![];
+    for &(c, u) in terms.iter().filter(|&&(c, u)| c > 0 && u > 0) {
+        match merged.iter_mut().find(|(c_m, _)| *c_m == c) {
+            Some((_, u_m)) => *u_m += u,
+            None => merged.push((c, u)),
+        }
+    }
+    merged.sort_unstable_by_key(|&(c, _)| -c);
+    let n = merged.len();
+    // maximum and gcd of coefficients of the remaining terms
+    let mut max_rest = vec![0; n + 1];
+    let mut gcd_rest = vec![0; n + 1];
+    for k in (0..n).rev() {
+        max_rest[k] = max_rest[k + 1] + merged[k].0 * merged[k].1;
+        gcd_rest[k] = num::integer::gcd(gcd_rest[k + 1], merged[k].0);
+    }
+
+    fn search(
+        terms: &[(i128, i128)],
+        k: usize,
+        rhs: i128,
+        max_rest: &[i128],
+        gcd_rest: &[i128],
+        work: &mut usize,
+    ) -> Option<bool> {
+        if rhs < 0 || rhs > max_rest[k] {
+            return Some(false);
+        }
+        if k == terms.len() || rhs == 0 {
+            return Some(rhs == 0);
+        }
+        if rhs % gcd_rest[k] != 0 {
+            return Some(false);
+        }
+        let (c, u) = terms[k];
+        let lo = ((rhs - max_rest[k + 1]).max(0) + c - 1) / c;
+        let hi = (rhs / c).min(u);
+        for x in (lo..=hi).rev() {
+            *work += 1;
+            if *work > OVERLAP_MAX_WORK {
+                return None;
+            }
+            if search(terms, k + 1, rhs - c * x, max_rest, gcd_rest, work)? {
+                return Some(true);
+            }
+        }
+        Some(false)
+    }
+
+    search(&merged, 0, rhs, &max_rest, &gcd_rest, &mut 0)
+}
+
+/// Minimum offset and terms $(|t_k|, n_k - 1)$ of a layout, with strides scaled by `size`.
+///
+/// Returns `None` if the layout contains no element.
+fn layout_terms(shape: &[isize], stride: &[isize], size: i128) -> Option<(i128, Vec<(i128, i128)>)> {
+    if shape.contains(&0) {
+        return None;
+    }
+    let min =
+        shape.iter().zip(stride).filter(|(_, &t)| t < 0).map(|(&s, &t)| (s as i128 - 1) * t as i128).sum::<i128>();
+    let terms = shape.iter().zip(stride).map(|(&s, &t)| ((t as i128).abs() * size, s as i128 - 1)).collect();
+    Some((min * size, terms))
+}
+
+/// Whether two layouts (with data address and element size in bytes) share some byte of memory.
+///
+/// Returns `None` if not decided within bounded search.
+pub(crate) fn layouts_overlap(
+    (addr_a, shape_a, stride_a, size_a): (usize, &[isize], &[isize], usize),
+    (addr_b, shape_b, stride_b, size_b): (usize, &[isize], &[isize], usize),
+) -> Option<bool> {
+    let (size_a, size_b) = (size_a as i128, size_b as i128);
+    let Some((min_a, terms_a)) = layout_terms(shape_a, stride_a, size_a) else { return Some(false) };
+    let Some((min_b, terms_b)) = layout_terms(shape_b, stride_b, size_b) else { return Some(false) };
+    // byte `addr_a + min_a + sum(c x) + p` equals byte `addr_b + min_b + sum(c y) + q`; substitute
+    // `y -> u - y` and `q -> size_b - 1 - q`, so that all coefficients are positive
+    let mut terms = terms_a;
+    terms.extend(&terms_b);
+    terms.push((1, size_a - 1 + size_b - 1));
+    let max_b = terms_b.iter().map(|&(c, u)| c * u).sum::<i128>();
+    let rhs = (addr_b as i128 + min_b) - (addr_a as i128 + min_a) + max_b + size_b - 1;
+    bounded_solvable(&terms, rhs)
+}
+
+/// Whether different indices of a layout refer to the same memory location.
+///
+/// Different from [`layout_self_overlapping`], this check is exact, but returns `None` if not
+/// decided within bounded search.
+pub(crate) fn layout_internal_overlap(shape: &[isize], stride: &[isize]) -> Option<bool> {
+    if shape.contains(&0) {
+        return Some(false);
+    }
+    let dims = shape
+        .iter()
+        .zip(stride)
+        .filter(|(&s, _)| s > 1)
+        .map(|(&s, &t)| ((t as i128).abs(), s as i128 - 1))
+        .collect::<Vec<_>>();
+    if dims.iter().any(|&(t, _)| t == 0) {
+        return Some(true);
+    }
+    // index difference `d` is non-zero: its first non-zero element `d_k` is positive (in `1..=u_k`),
+    // other elements `d_j` (j > k) are in `-u_j..=u_j`, shifted to `0..=2 u_j`
+    let mut undecided = false;
+    for (k, &(t_k, u_k)) in dims.iter().enumerate() {
+        let mut terms = vec![(t_k, u_k - 1)];
+        terms.extend(dims[k + 1..].iter().map(|&(t, u)| (t, 2 * u)));
+        let rhs = dims[k + 1..].iter().map(|&(t, u)| t * u).sum::<i128>() - t_k;
+        match bounded_solvable(&terms, rhs) {
+            Some(true) => return Some(true),
+            Some(false) => (),
+            None => undecided = true,
+        }
+    }
+    if undecided {
+        None
+    } else {
+        Some(false)
+    }
+}
+
+/// Check that the layout is valid for a slice of length `len`.
+fn check_layout(shape: &[isize], stride: &[isize], len: usize, mutable: bool) -> Result<(), TblisError> {
+    let err_layout = || TblisError::InvalidLayout { shape: shape.to_vec(), stride: stride.to_vec(), len };
+    if shape.len() != stride.len() || shape.iter().any(|&s| s < 0) {
+        return Err(err_layout());
+    }
+    if let Some((min, max)) = layout_extent(shape, stride).map_err(|_| err_layout())? {
+        if min < 0 || max >= len as isize {
+            return Err(err_layout());
+        }
+    }
+    if mutable && layout_self_overlapping(shape, stride) {
+        return Err(TblisError::SelfOverlap { shape: shape.to_vec(), stride: stride.to_vec() });
+    }
+    Ok(())
+}
+
+/* #endregion */
+
+/* #region view structs */
+
+/// Immutable tensor view borrowed from a slice.
+///
+/// The first element of slice corresponds to index zero of the tensor. Negative strides are not
+/// allowed to go beyond the start of slice.
+#[derive(Debug, Clone)]
+pub struct TblisView<'a, T>
+where
+    T: TblisFloatAPI,
+{
+    tsr: TblisTensor<T>,
+    _phantom: PhantomData<&'a [T]>,
+}
+
+/// Mutable tensor view borrowed from a slice.
+///
+/// Different from [`TblisView`], the layout of a mutable view is not allowed to be self-overlapping
+/// (e.g. stride 0 with dimension larger than 1).
+///
+/// Views always have unit `scalar` and no `conj` in the underlying tensor; operations writing to a
+/// view only change its elements, so the view can be used as operand afterwards.
+#[derive(Debug)]
+pub struct TblisViewMut<'a, T>
+where
+    T: TblisFloatAPI,
+{
+    tsr: TblisTensor<T>,
+    _phantom: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T> TblisView<'a, T>
+where
+    T: TblisFloatAPI,
+{
+    /// Create a view from slice with given shape and stride.
+    ///
+    /// # Panics
+    ///
+    /// - If the layout is out of bounds of the slice.
+    ///
+    /// # See also
+    ///
+    /// - [`TblisView::new_f`] for fallible version.
+    pub fn new(data: &'a [T], shape: &[isize], stride: &[isize]) -> Self {
+        Self::new_f(data, shape, stride).unwrap()
+    }
+
+    /// Create a view from slice with given shape and stride.
+    pub fn new_f(data: &'a [T], shape: &[isize], stride: &[isize]) -> Result<Self, TblisError> {
+        check_layout(shape, stride, data.len(), false)?;
+        let tsr = TblisTensor::new(data.as_ptr() as *mut T, shape, stride);
+        Ok(Self { tsr, _phantom: PhantomData })
+    }
+
+    /// Create a contiguous view from slice with given shape.
+    ///
+    /// # Panics
+    ///
+    /// - If the size of shape is larger than the slice length.
+    pub fn from_shape(data: &'a [T], shape: &[isize], row_major: bool) -> Self {
+        Self::new(data, shape, &shape_to_stride(shape, row_major))
+    }
+
+    /// Underlying TBLIS tensor (shape, stride and data pointer).
+    pub fn as_tensor(&self) -> &TblisTensor<T> {
+        &self.tsr
+    }
+
+    pub fn shape(&self) -> &[isize] {
+        &self.tsr.shape
+    }
+
+    pub fn stride(&self) -> &[isize] {
+        &self.tsr.stride
+    }
+}
+
+impl<'a, T> TblisViewMut<'a, T>
+where
+    T: TblisFloatAPI,
+{
+    /// Create a mutable view from slice with given shape and stride.
+    ///
+    /// # Panics
+    ///
+    /// - If the layout is out of bounds of the slice.
+    /// - If the layout is self-overlapping.
+    ///
+    /// # See also
+    ///
+    /// - [`TblisViewMut::new_f`] for fallible version.
+    pub fn new(data: &'a mut [T], shape: &[isize], stride: &[isize]) -> Self {
+        Self::new_f(data, shape, stride).unwrap()
+    }
+
+    /// Create a mutable view from slice with given shape and stride.
+    pub fn new_f(data: &'a mut [T], shape: &[isize], stride: &[isize]) -> Result<Self, TblisError> {
+        check_layout(shape, stride, data.len(), true)?;
+        let tsr = TblisTensor::new(data.as_mut_ptr(), shape, stride);
+        Ok(Self { tsr, _phantom: PhantomData })
+    }
+
+    /// Create a contiguous mutable view from slice with given shape.
+    ///
+    /// # Panics
+    ///
+    /// - If the size of shape is larger than the slice length.
+    pub fn from_shape(data: &'a mut [T], shape: &[isize], row_major: bool) -> Self {
+        Self::new(data, shape, &shape_to_stride(shape, row_major))
+    }
+
+    /// Immutable view that borrows this mutable view.
+    pub fn view(&self) -> TblisView<'_, T> {
+        TblisView { tsr: self.tsr.clone(), _phantom: PhantomData }
+    }
+
+    /// Underlying TBLIS tensor (shape, stride and data pointer).
+    pub fn as_tensor(&self) -> &TblisTensor<T> {
+        &self.tsr
+    }
+
+    pub fn shape(&self) -> &[isize] {
+        &self.tsr.shape
+    }
+
+    pub fn stride(&self) -> &[isize] {
+        &self.tsr.stride
+    }
+}
+
+impl<'a, T> From<TblisViewMut<'a, T>> for TblisView<'a, T>
+where
+    T: TblisFloatAPI,
+{
+    fn from(view: TblisViewMut<'a, T>) -> Self {
+        TblisView { tsr: view.tsr, _phantom: PhantomData }
+    }
+}
+
+/* #endregion */
+
+/* #region safe operations */
+
+/// Safe version of [`tblis_tensor_add`] on views.
+pub fn tblis_view_add<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    b: &mut TblisViewMut<T>,
+    idx_b: &str,
+    cfg: Option<TblisAddCfg<T>>,
+) where
+    T: TblisFloatAPI,
+{
+    tblis_view_add_f(a, idx_a, b, idx_b, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_add_f`] on views.
+pub fn tblis_view_add_f<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    b: &mut TblisViewMut<T>,
+    idx_b: &str,
+    cfg: Option<TblisAddCfg<T>>,
+) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_add_f(&a.tsr, idx_a, &mut b.tsr, idx_b, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_dot`] on views.
+pub fn tblis_view_dot<T>(a: &TblisView<T>, idx_a: &str, b: &TblisView<T>, idx_b: &str, cfg: Option<TblisDotCfg<T>>) -> T
+where
+    T: TblisFloatAPI,
+{
+    tblis_view_dot_f(a, idx_a, b, idx_b, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_dot_f`] on views.
+pub fn tblis_view_dot_f<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    b: &TblisView<T>,
+    idx_b: &str,
+    cfg: Option<TblisDotCfg<T>>,
+) -> Result<T, TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_dot_f(&a.tsr, idx_a, &b.tsr, idx_b, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_mult`] on views.
+pub fn tblis_view_mult<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    b: &TblisView<T>,
+    idx_b: &str,
+    c: &mut TblisViewMut<T>,
+    idx_c: &str,
+    cfg: Option<TblisMultCfg<T>>,
+) where
+    T: TblisFloatAPI,
+{
+    tblis_view_mult_f(a, idx_a, b, idx_b, c, idx_c, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_mult_f`] on views.
+pub fn tblis_view_mult_f<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    b: &TblisView<T>,
+    idx_b: &str,
+    c: &mut TblisViewMut<T>,
+    idx_c: &str,
+    cfg: Option<TblisMultCfg<T>>,
+) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_mult_f(&a.tsr, idx_a, &b.tsr, idx_b, &mut c.tsr, idx_c, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_reduce`] on views.
+pub fn tblis_view_reduce<T>(a: &TblisView<T>, idx_a: &str, op: TblisReduceOp, cfg: Option<TblisReduceCfg<T>>) -> T
+where
+    T: TblisFloatAPI,
+{
+    tblis_view_reduce_f(a, idx_a, op, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_reduce_f`] on views.
+pub fn tblis_view_reduce_f<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> Result<T, TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_reduce_f(&a.tsr, idx_a, op, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_reduce_with_index`] on views.
+pub fn tblis_view_reduce_with_index<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> (T, isize, Vec<isize>)
+where
+    T: TblisFloatAPI,
+{
+    tblis_view_reduce_with_index_f(a, idx_a, op, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_reduce_with_index_f`] on views.
+pub fn tblis_view_reduce_with_index_f<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> Result<(T, isize, Vec<isize>), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_reduce_with_index_f(&a.tsr, idx_a, op, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_norm`] on views.
+pub fn tblis_view_norm<T>(a: &TblisView<T>, idx_a: &str, kind: TblisNormKind, cfg: Option<TblisReduceCfg<T>>) -> T::Real
+where
+    T: TblisFloatAPI,
+{
+    tblis_view_norm_f(a, idx_a, kind, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_norm_f`] on views.
+pub fn tblis_view_norm_f<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    kind: TblisNormKind,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> Result<T::Real, TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_norm_f(&a.tsr, idx_a, kind, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_reduce_partial`] on views.
+pub fn tblis_view_reduce_partial<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    b: &mut TblisViewMut<T>,
+    idx_b: &str,
+    cfg: Option<TblisReduceCfg<T>>,
+) where
+    T: TblisFloatAPI,
+{
+    tblis_view_reduce_partial_f(a, idx_a, op, b, idx_b, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_reduce_partial_f`] on views.
+pub fn tblis_view_reduce_partial_f<T>(
+    a: &TblisView<T>,
+    idx_a: &str,
+    op: TblisReduceOp,
+    b: &mut TblisViewMut<T>,
+    idx_b: &str,
+    cfg: Option<TblisReduceCfg<T>>,
+) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_reduce_partial_f(&a.tsr, idx_a, op, &mut b.tsr, idx_b, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_scale`] on views.
+pub fn tblis_view_scale<T>(a: &mut TblisViewMut<T>, idx_a: &str, cfg: Option<TblisScaleCfg<T>>)
+where
+    T: TblisFloatAPI,
+{
+    tblis_view_scale_f(a, idx_a, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_scale_f`] on views.
+pub fn tblis_view_scale_f<T>(
+    a: &mut TblisViewMut<T>,
+    idx_a: &str,
+    cfg: Option<TblisScaleCfg<T>>,
+) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_scale_f(&mut a.tsr, idx_a, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_set`] on views.
+pub fn tblis_view_set<T>(a: &mut TblisViewMut<T>, idx_a: &str, alpha: T, cfg: Option<TblisSetCfg>)
+where
+    T: TblisFloatAPI,
+{
+    tblis_view_set_f(a, idx_a, alpha, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_set_f`] on views.
+pub fn tblis_view_set_f<T>(
+    a: &mut TblisViewMut<T>,
+    idx_a: &str,
+    alpha: T,
+    cfg: Option<TblisSetCfg>,
+) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_set_f(&mut a.tsr, idx_a, alpha, cfg) }
+}
+
+/// Safe version of [`tblis_tensor_shift`] on views.
+pub fn tblis_view_shift<T>(a: &mut TblisViewMut<T>, idx_a: &str, alpha: T, cfg: Option<TblisShiftCfg<T>>)
+where
+    T: TblisFloatAPI,
+{
+    tblis_view_shift_f(a, idx_a, alpha, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_tensor_shift_f`] on views.
+pub fn tblis_view_shift_f<T>(
+    a: &mut TblisViewMut<T>,
+    idx_a: &str,
+    alpha: T,
+    cfg: Option<TblisShiftCfg<T>>,
+) -> Result<(), TblisError>
+where
+    T: TblisFloatAPI,
+{
+    unsafe { tblis_tensor_shift_f(&mut a.tsr, idx_a, alpha, cfg) }
+}
+
+/// Safe version of [`tblis_einsum`] on views.
+///
+/// All the parameters are the same as in [`tblis_einsum`], except for `operands` and `out` being
+/// views.
+pub fn tblis_einsum_view<T>(
+    subscripts: &str,
+    operands: &[&TblisView<T>],
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+    row_major: bool,
+    out: Option<&mut TblisViewMut<T>>,
+    cfg: Option<TblisEinsumCfg<T>>,
+) -> Option<TblisArray<T>>
+where
+    T: TblisFloatAPI,
+{
+    tblis_einsum_view_f(subscripts, operands, optimize, memory_limit, row_major, out, cfg).unwrap()
+}
+
+/// Safe version of [`tblis_einsum_f`] on views.
+pub fn tblis_einsum_view_f<T>(
+    subscripts: &str,
+    operands: &[&TblisView<T>],
+    optimize: impl PathOptimizer,
+    memory_limit: impl Into<SizeLimitType>,
+    row_major: bool,
+    out: Option<&mut TblisViewMut<T>>,
+    cfg: Option<TblisEinsumCfg<T>>,
+) -> Result<Option<TblisArray<T>>, TblisError>
+where
+    T: TblisFloatAPI,
+{
+    let operands = operands.iter().map(|x| &x.tsr).collect::<Vec<_>>();
+    let out = out.map(|x| &mut x.tsr);
+    unsafe { tblis_einsum_f(subscripts, &operands, optimize, memory_limit, row_major, out, cfg) }
+}
+
+impl<T> TblisEinsumPlan<T>
+where
+    T: TblisFloatAPI,
+{
+    /// Safe version of [`TblisEinsumPlan::execute`] on views.
+    pub fn execute_view(
+        &self,
+        operands: &[&TblisView<T>],
+        out: Option<&mut TblisViewMut<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+    ) -> Option<TblisArray<T>> {
+        self.execute_view_f(operands, out, cfg).unwrap()
+    }
+
+    /// Safe version of [`TblisEinsumPlan::execute_f`] on views.
+    pub fn execute_view_f(
+        &self,
+        operands: &[&TblisView<T>],
+        out: Option<&mut TblisViewMut<T>>,
+        cfg: Option<TblisEinsumCfg<T>>,
+    ) -> Result<Option<TblisArray<T>>, TblisError> {
+        let operands = operands.iter().map(|x| &x.tsr).collect::<Vec<_>>();
+        let out = out.map(|x| &mut x.tsr);
+        unsafe { self.execute_f(&operands, out, cfg) }
+    }
+}
+
+/* #endregion */
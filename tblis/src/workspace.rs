@@ -0,0 +1,267 @@
+//! Workspace (buffer arena) for intermediates of einsum execution.
+
+use crate::prelude::*;
+use std::sync::Arc;
+
+/// Workspace (buffer arena) for intermediates of einsum plan execution.
+///
+/// Without workspace, each contraction step of [`TblisEinsumPlan::execute`] allocates its output
+/// (and traced operands), and deallocates them when consumed. A workspace keeps these buffers
+/// alive and recycles them across steps and executions.
+///
+/// Buffers are assigned to intermediates by slots (the lowest free slot is always taken), so the
+/// assignment is the same in every execution of a plan. After the workspace is sized for a plan
+/// (by [`TblisWorkspace::for_plan`], [`TblisWorkspace::reserve`], or simply by the first
+/// execution), executions of that plan perform no heap allocation for intermediates. The output
+/// is still allocated, unless pre-allocated output is provided.
+///
+/// Buffers are allocated by the allocator of workspace (see [`TblisAllocator`]), and released to
+/// it when the workspace is dropped or a buffer is grown.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate tblis_src;
+/// use tblis::prelude::*;
+///
+/// let vec_a: Vec<f64> = (0..100).map(|x| x as f64).collect();
+/// let tsr_a = TblisTensor::new(vec_a.as_ptr() as *mut f64, &[10, 10], &[10, 1]);
+/// let mut vec_out = vec![0.0; 100];
+/// let mut tsr_out = TblisTensor::new(vec_out.as_mut_ptr(), &[10, 10], &[10, 1]);
+///
+/// let shapes = [vec![10, 10], vec![10, 10], vec![10, 10], vec![10, 10]];
+/// let plan = TblisEinsumPlan::<f64>::new("ij,jk,kl,lm->im", &shapes, "optimal", None, true);
+/// let mut workspace = TblisWorkspace::for_plan(&plan);
+/// let size = workspace.size();
+/// for _ in 0..3 {
+///     let operands = [&tsr_a, &tsr_a, &tsr_a, &tsr_a];
+///     unsafe { plan.execute_with_workspace(&operands, Some(&mut tsr_out), None, &mut workspace) };
+///     assert_eq!(workspace.size(), size);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TblisWorkspace<T>
+where
+    T: TblisFloatAPI,
+{
+    /// buffers, released to allocator when dropped
+    buffers: Vec<TblisArray<T>>,
+    slots: TblisSlots,
+    allocator: Arc<dyn TblisAllocator<T>>,
+    /// bytes of outputs allocated internally in the current execution
+    output_bytes: usize,
+    /// measured peak bytes of buffers and outputs
+    peak_bytes: usize,
+}
+
+impl<T> Default for TblisWorkspace<T>
+where
+    T: TblisFloatAPI,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TblisWorkspace<T>
+where
+    T: TblisFloatAPI,
+{
+    /// Create an empty workspace; buffers are allocated on demand.
+    pub fn new() -> Self {
+        Self::with_allocator(Arc::new(TblisDefaultAllocator))
+    }
+
+    /// Create an empty workspace, with buffers allocated by `allocator`.
+    pub fn with_allocator(allocator: Arc<dyn TblisAllocator<T>>) -> Self {
+        Self { buffers: vec![], slots: TblisSlots::default(), allocator, output_bytes: 0, peak_bytes: 0 }
+    }
+
+    /// Allocator of workspace buffers.
+    pub fn allocator(&self) -> &Arc<dyn TblisAllocator<T>> {
+        &self.allocator
+    }
+
+    /// Create a workspace sized for the plan.
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if allocation failed. Use [`TblisWorkspace::for_plan_f`] for
+    ///   fallible version.
+    pub fn for_plan(plan: &TblisEinsumPlan<T>) -> Self {
+        Self::for_plan_f(plan).unwrap()
+    }
+
+    /// Create a workspace sized for the plan.
+    ///
+    /// # See also
+    ///
+    /// [`TblisWorkspace::for_plan`] for non-fallible version.
+    pub fn for_plan_f(plan: &TblisEinsumPlan<T>) -> Result<Self, TblisError> {
+        Self::for_plan_in_f(plan, Arc::new(TblisDefaultAllocator))
+    }
+
+    /// Create a workspace sized for the plan, with buffers allocated by `allocator`.
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if allocation failed. Use [`TblisWorkspace::for_plan_in_f`] for
+    ///   fallible version.
+    pub fn for_plan_in(plan: &TblisEinsumPlan<T>, allocator: Arc<dyn TblisAllocator<T>>) -> Self {
+        Self::for_plan_in_f(plan, allocator).unwrap()
+    }
+
+    /// Create a workspace sized for the plan, with buffers allocated by `allocator`.
+    ///
+    /// # See also
+    ///
+    /// [`TblisWorkspace::for_plan_in`] for non-fallible version.
+    pub fn for_plan_in_f(plan: &TblisEinsumPlan<T>, allocator: Arc<dyn TblisAllocator<T>>) -> Result<Self, TblisError> {
+        let mut workspace = Self::with_allocator(allocator);
+        workspace.reserve_f(plan)?;
+        Ok(workspace)
+    }
+
+    /// Grow buffers of workspace, so that executing the plan performs no allocation of
+    /// intermediates.
+    ///
+    /// # Panics
+    ///
+    /// - This function will panic if allocation failed. Use [`TblisWorkspace::reserve_f`] for
+    ///   fallible version.
+    pub fn reserve(&mut self, plan: &TblisEinsumPlan<T>) {
+        self.reserve_f(plan).unwrap()
+    }
+
+    /// Grow buffers of workspace, so that executing the plan performs no allocation of
+    /// intermediates.
+    ///
+    /// # See also
+    ///
+    /// [`TblisWorkspace::reserve`] for non-fallible version.
+    pub fn reserve_f(&mut self, plan: &TblisEinsumPlan<T>) -> Result<(), TblisError> {
+        for (slot, size) in plan.workspace_sizes().into_iter().enumerate() {
+            if slot == self.buffers.len() || self.buffers[slot].capacity() < size {
+                unsafe { self.alloc_f(slot, &[size as isize], true)? };
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of buffers held by workspace.
+    pub fn num_buffers(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Total number of elements of buffers held by workspace.
+    pub fn size(&self) -> usize {
+        self.buffers.iter().map(|arr| arr.capacity()).sum()
+    }
+
+    /// Measured peak memory (in bytes) held by buffers of workspace and outputs allocated
+    /// internally in executions, since creation of workspace or the last
+    /// [`TblisWorkspace::reset_peak`].
+    ///
+    /// A buffer being grown is counted together with its replacement. Alignment padding is not
+    /// counted. For the peak computed before execution, see [`TblisEinsumPlan::peak_bytes`].
+    pub fn peak_bytes(&self) -> usize {
+        self.peak_bytes
+    }
+
+    /// Reset measured peak memory to bytes of buffers currently held.
+    pub fn reset_peak(&mut self) {
+        self.peak_bytes = self.size() * core::mem::size_of::<T>();
+    }
+
+    /// Record output allocated internally in the current execution.
+    pub(crate) fn add_output_bytes(&mut self, bytes: usize) {
+        self.output_bytes += bytes;
+        self.update_peak(0);
+    }
+
+    /// Called at the end of each plan execution; outputs are handed over to the caller.
+    pub(crate) fn end_execution(&mut self) {
+        self.output_bytes = 0;
+    }
+
+    /// Update measured peak, with `extra` bytes not yet held by workspace.
+    fn update_peak(&mut self, extra: usize) {
+        let held = self.size() * core::mem::size_of::<T>() + self.output_bytes + extra;
+        self.peak_bytes = self.peak_bytes.max(held);
+    }
+
+    /// Mark all buffers as free; called at the start of each plan execution.
+    pub(crate) fn release_all(&mut self) {
+        self.slots.reset(self.buffers.len());
+    }
+
+    /// Take a buffer for (uninitialized) tensor of shape; returns slot and tensor.
+    pub(crate) unsafe fn take_f(
+        &mut self,
+        shape: &[isize],
+        row_major: bool,
+    ) -> Result<(usize, TblisTensor<T>), TblisError> {
+        let size = shape.iter().product::<isize>().max(0) as usize;
+        let slot = self.slots.take();
+        if slot == self.buffers.len() || self.buffers[slot].capacity() < size {
+            unsafe { self.alloc_f(slot, shape, row_major) }.inspect_err(|_| self.slots.give(slot))?;
+        } else {
+            self.buffers[slot].reshape_uninit(shape, row_major);
+        }
+        Ok((slot, self.buffers[slot].to_tblis_tensor_mut()))
+    }
+
+    /// Allocate buffer of slot (new slot, or replacing the previous buffer).
+    unsafe fn alloc_f(&mut self, slot: usize, shape: &[isize], row_major: bool) -> Result<(), TblisError> {
+        let arr = unsafe { TblisArray::uninit_in_f(shape, row_major, &*self.allocator)? };
+        self.update_peak(arr.capacity() * core::mem::size_of::<T>());
+        if slot == self.buffers.len() {
+            self.buffers.push(arr);
+        } else {
+            // previous buffer is released to allocator when dropped
+            self.buffers[slot] = arr;
+        }
+        Ok(())
+    }
+
+    /// Return buffer of slot for later reuse.
+    pub(crate) fn give(&mut self, slot: usize) {
+        self.slots.give(slot);
+    }
+}
+
+/// (dev-only) Slot assignment of workspace buffers.
+///
+/// The lowest free slot is always taken, so that the assignment only depends on the sequence of
+/// take/give, not on the buffers already held.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TblisSlots {
+    in_use: Vec<bool>,
+}
+
+impl TblisSlots {
+    /// Mark all slots as free, keeping `num_slots` slots.
+    pub(crate) fn reset(&mut self, num_slots: usize) {
+        self.in_use.clear();
+        self.in_use.resize(num_slots, false);
+    }
+
+    /// Take the lowest free slot (or a new slot).
+    pub(crate) fn take(&mut self) -> usize {
+        match self.in_use.iter().position(|&used| !used) {
+            Some(slot) => {
+                self.in_use[slot] = true;
+                slot
+            },
+            None => {
+                self.in_use.push(true);
+                self.in_use.len() - 1
+            },
+        }
+    }
+
+    /// Mark slot as free.
+    pub(crate) fn give(&mut self, slot: usize) {
+        self.in_use[slot] = false;
+    }
+}
@@ -41,8 +41,8 @@ fn test() {
         let time = std::time::Instant::now();
         let mut vec_g = vec![];
         for _ in 0..nrepeat {
-            let (vec_g_temp, _) = unsafe { tblis_einsum(subscripts, &operands, true, None, true, None).unwrap() };
-            vec_g = vec_g_temp;
+            let arr_g = unsafe { tblis_einsum(subscripts, &operands, true, None, true, None, None).unwrap() };
+            vec_g = arr_g.into_vec();
         }
         println!("elapsed time: {:12.6?} (avg of {nrepeat:2} repeats)", time.elapsed() / nrepeat);
         println!("fingerprint : {:20.12}", fp(&vec_g));
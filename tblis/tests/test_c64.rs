@@ -144,8 +144,369 @@ fn test_einsum(#[case] einsum_str: &str, #[case] ref_shape: Vec<isize>, #[case]
     let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
     let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
     let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
-    let (out_data, out_tensor) = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, None).unwrap() };
-    let out_fp = fp(&out_data);
-    assert_eq!(out_tensor.shape, ref_shape);
+    let out = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, None, None).unwrap() };
+    let out_fp = fp(out.as_slice());
+    assert_eq!(out.shape(), ref_shape);
     assert!((out_fp - ref_fp).abs() < 1e-10);
 }
+
+#[test]
+fn test_dot_reduce() {
+    let mut vec_a = vec![c64::new(1.0, 2.0), c64::new(3.0, -1.0)];
+    let mut vec_b = vec![c64::new(2.0, 0.0), c64::new(0.0, 1.0)];
+    let tsr_a = TblisTensor::new(vec_a.as_mut_ptr(), &[2], &[1]);
+    let tsr_b = TblisTensor::new(vec_b.as_mut_ptr(), &[2], &[1]);
+    assert_eq!(unsafe { tblis_tensor_dot(&tsr_a, "a", &tsr_b, "a", None) }, c64::new(3.0, 7.0));
+    let cfg = TblisDotCfgBuilder::default().conja(true).build().unwrap();
+    assert_eq!(unsafe { tblis_tensor_dot(&tsr_a, "a", &tsr_b, "a", Some(cfg)) }, c64::new(1.0, -1.0));
+    assert_eq!(unsafe { tblis_tensor_reduce(&tsr_a, "a", TblisReduceOp::Sum, None) }, c64::new(4.0, 1.0));
+}
+
+#[test]
+fn test_norm() {
+    let (vec_a, tsr_a) = build_tblis_tensor("abc");
+    let norm1: f64 = unsafe { tblis_tensor_norm(&tsr_a, "abc", TblisNormKind::Norm1, None) };
+    let norm2: f64 = unsafe { tblis_tensor_norm(&tsr_a, "abc", TblisNormKind::Norm2, None) };
+    let norminf: f64 = unsafe { tblis_tensor_norm(&tsr_a, "abc", TblisNormKind::NormInf, None) };
+    assert!((norm1 - vec_a.iter().map(|x| x.abs()).sum::<f64>()).abs() < 1e-10);
+    assert!((norm2 - vec_a.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt()).abs() < 1e-10);
+    assert!((norminf - vec_a.iter().map(|x| x.abs()).fold(0.0, f64::max)).abs() < 1e-10);
+
+    // scaled by |alpha|
+    let cfg = TblisReduceCfgBuilder::default().alpha(c64::new(0.0, 2.0)).build().unwrap();
+    let norm2_scaled = unsafe { tblis_tensor_norm(&tsr_a, "abc", TblisNormKind::Norm2, Some(cfg)) };
+    assert!((norm2_scaled - 2.0 * norm2).abs() < 1e-10);
+
+    // ordering is not defined for complex
+    const { assert!(c64::IS_COMPLEX && !f64::IS_COMPLEX) };
+    for op in [TblisReduceOp::Max, TblisReduceOp::Min] {
+        let res = unsafe { tblis_tensor_reduce_f(&tsr_a, "abc", op, None) };
+        assert!(matches!(res, Err(TblisError::UnsupportedReduction { .. })));
+    }
+    let res = unsafe { tblis_tensor_reduce_with_index_f(&tsr_a, "abc", TblisReduceOp::MaxAbs, None) };
+    assert!(res.is_ok());
+}
+
+#[rstest]
+#[case("a,ab,b->")]
+#[case("ab,bc->ac")]
+#[case("aab,bc->ac")]
+#[case("ab->ba")]
+#[case(",ab->ab")]
+#[case("a,b,ab->ab")]
+fn test_einsum_conj_scalar(#[case] einsum_str: &str) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let mut tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let scalars = [c64::new(0.5, 0.0), c64::new(0.3, -1.2), c64::new(-2.0, 0.7)];
+
+    // reference: conjugate and scale data explicitly
+    let mut tensors_ref = tensors.iter().map(|(data, _)| data.clone()).collect::<Vec<_>>();
+    for (i, data) in tensors_ref.iter_mut().enumerate() {
+        let scalar = scalars[i % 3];
+        data.iter_mut().for_each(|x| *x = if i % 2 == 0 { scalar * x.conj() } else { scalar * *x });
+    }
+    let tblis_tensors_ref = tensors
+        .iter()
+        .zip(tensors_ref.iter_mut())
+        .map(|((_, t), data)| TblisTensor::new(data.as_mut_ptr(), &t.shape, &t.stride))
+        .collect::<Vec<_>>();
+    let tblis_tensors_ref = tblis_tensors_ref.iter().collect::<Vec<_>>();
+    let out_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors_ref, true, None, true, None, None).unwrap() };
+
+    // operands marked with conj and scalar
+    for (i, (_, t)) in tensors.iter_mut().enumerate() {
+        t.set_conj(i % 2 == 0).set_scalar(scalars[i % 3]);
+    }
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let out = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, None, None).unwrap() };
+    assert_eq!(out.shape(), out_ref.shape());
+    assert!((fp(out.as_slice()) - fp(out_ref.as_slice())).abs() < 1e-10);
+}
+
+#[rstest]
+#[case("ij,j->i"     , [false, true , false], true )]
+#[case("ij,j->i"     , [false, true , false], false)]
+#[case("ab,bc"       , [true , false, false], true )]
+#[case("aab,bc->ca"  , [true , false, false], false)]
+#[case("a,ab,b->"    , [true , false, true ], true )]
+#[case("a,ab,b->"    , [false, false, false], true )]
+#[case("cab,b,c->a"  , [false, false, true ], true )]
+fn test_einsum_mixed(#[case] einsum_str: &str, #[case] is_complex: [bool; 3], #[case] row_major: bool) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let mut tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    // real operands are real parts of complex tensors
+    let mut tensors_re = tensors
+        .iter()
+        .map(|(data, t)| {
+            let mut data_re = data.iter().map(|x| x.re).collect::<Vec<f64>>();
+            let tsr = TblisTensor::new(data_re.as_mut_ptr(), &t.shape, &t.stride);
+            (data_re, tsr)
+        })
+        .collect::<Vec<_>>();
+    for (i, (data, t)) in tensors.iter_mut().enumerate() {
+        if !is_complex[i] {
+            data.iter_mut().for_each(|x| x.im = 0.0);
+        }
+        t.set_scalar(c64::new(0.5 + i as f64, 0.0));
+        tensors_re[i].1.set_scalar(0.5 + i as f64);
+    }
+    if is_complex[0] {
+        tensors[0].1.set_conj(true).set_scalar(c64::new(0.3, -1.2));
+    }
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let out_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, row_major, None, None).unwrap() };
+
+    let operands = (0..einsum_str_inp.len())
+        .map(|i| if is_complex[i] { (&tensors[i].1).into() } else { (&tensors_re[i].1).into() })
+        .collect::<Vec<TblisMixedTensor<f64>>>();
+    let out = unsafe { tblis_einsum_mixed(einsum_str, &operands, true, None, row_major, None, None).unwrap() };
+    assert_eq!(out.is_complex(), is_complex.iter().take(einsum_str_inp.len()).any(|&x| x));
+    let mut out = out.into_complex();
+    assert_eq!(out.shape(), out_ref.shape());
+    assert_eq!(out.stride(), out_ref.stride());
+    assert!((fp(out.as_slice()) - fp(out_ref.as_slice())).abs() < 1e-10);
+
+    // accumulate to given output with complex alpha and beta
+    let (alpha, beta) = (c64::new(0.7, 0.4), c64::new(-0.2, 1.1));
+    let prev = out.as_slice().to_vec();
+    let cfg = TblisEinsumCfgBuilder::default().alpha(alpha).beta(beta).build().unwrap();
+    let mut tsr_out = out.to_tblis_tensor_mut();
+    let res =
+        unsafe { tblis_einsum_mixed(einsum_str, &operands, true, None, row_major, Some(&mut tsr_out), Some(cfg)) };
+    assert!(res.is_none());
+    let expected = prev.iter().zip(out_ref.as_slice()).map(|(&p, &r)| alpha * r + beta * p).collect::<Vec<_>>();
+    assert!((fp(out.as_slice()) - fp(&expected)).abs() < 1e-10);
+}
+
+#[test]
+fn test_einsum_mixed_ellipsis() {
+    // labels of re/im axes must not clash with labels of ellipsis dimensions
+    let subscripts = "...i,...i->...";
+    let (shape_a, shape_b) = ([2, 3, 4], [3, 4]);
+    let mut vec_a = gen_array(&shape_a);
+    let mut vec_b = gen_array(&shape_b).iter().map(|x| x.conj()).collect::<Vec<c64>>();
+    let tsr_a = TblisTensor::new(vec_a.as_mut_ptr(), &shape_a, &build_strides(&shape_a));
+    let tsr_b = TblisTensor::new(vec_b.as_mut_ptr(), &shape_b, &build_strides(&shape_b));
+    let out_ref = unsafe { tblis_einsum(subscripts, &[&tsr_a, &tsr_b], true, None, true, None, None).unwrap() };
+    assert_eq!(out_ref.shape(), [2, 3]);
+
+    let operands: [TblisMixedTensor<f64>; 2] = [(&tsr_a).into(), (&tsr_b).into()];
+    let out = unsafe { tblis_einsum_mixed(subscripts, &operands, true, None, true, None, None).unwrap() };
+    let out = out.into_complex();
+    assert_eq!(out.shape(), out_ref.shape());
+    assert!((fp(out.as_slice()) - fp(out_ref.as_slice())).abs() < 1e-10);
+
+    // implicit output of ellipsis subscripts
+    let out = unsafe { tblis_einsum_mixed("i...j,...j", &operands, true, None, true, None, None).unwrap() };
+    let out_ref = unsafe { tblis_einsum("i...j,...j", &[&tsr_a, &tsr_b], true, None, true, None, None).unwrap() };
+    let out = out.into_complex();
+    assert_eq!(out.shape(), out_ref.shape());
+    assert!((fp(out.as_slice()) - fp(out_ref.as_slice())).abs() < 1e-10);
+}
+
+#[test]
+fn test_real_parts() {
+    let (vec_a, tsr_a) = build_tblis_tensor("abc");
+    let (mut vec_b, tsr_b) = build_tblis_tensor("cab");
+
+    // real view with re/im axis: transpose complex tensor by real add
+    let mut tsr_b_real = tsr_b.as_real_parts();
+    assert_eq!(tsr_b_real.shape, [4, 2, 3, 2]);
+    assert_eq!(tsr_b_real.stride, [12, 6, 2, 1]);
+    let cfg = TblisAddCfgBuilder::default().beta(0.0).build().unwrap();
+    unsafe { tblis_tensor_add(&tsr_a.as_real_parts(), "abcz", &mut tsr_b_real, "cabz", Some(cfg)) };
+    let arr_ref = unsafe { tblis_einsum("abc->cab", &[&tsr_a], true, None, true, None, None).unwrap() };
+    assert!((fp(&vec_b) - fp(arr_ref.as_slice())).abs() < 1e-10);
+
+    // real and imaginary parts: B.re = 2 A.im (transposed), B.im unchanged
+    let vec_b_prev = vec_b.clone();
+    let cfg = TblisAddCfgBuilder::default().alpha(2.0).beta(0.0).build().unwrap();
+    unsafe { tblis_tensor_add(&tsr_a.im(), "abc", &mut tsr_b.re(), "cab", Some(cfg)) };
+    let (a, b, c) = (1, 2, 3);
+    assert!((vec_b[c * 6 + a * 3 + b].re - 2.0 * vec_a[a * 12 + b * 4 + c].im).abs() < 1e-14);
+    assert!(vec_b.iter().zip(&vec_b_prev).all(|(x, y)| x.im == y.im));
+
+    // real part of B added into imaginary part of B (interleaved, not overlapping)
+    let vec_b_prev = vec_b.clone();
+    unsafe { tblis_tensor_add(&tsr_b.re(), "cab", &mut tsr_b.im(), "cab", None) };
+    assert!(vec_b.iter().zip(&vec_b_prev).all(|(x, y)| x.re == y.re && x.im == y.re + y.im));
+    let res = unsafe { tblis_tensor_add_f(&tsr_b.re(), "cab", &mut tsr_b.re(), "cab", None) };
+    assert_eq!(res, Err(TblisError::Aliasing { operand: 0 }));
+
+    // reverse: interleaved real buffer as complex
+    let tsr_b_cplx = tsr_b.as_real_parts().as_complex();
+    assert_eq!(tsr_b_cplx.shape, tsr_b.shape);
+    assert_eq!(tsr_b_cplx.stride, tsr_b.stride);
+    assert_eq!(tsr_b_cplx.data, vec_b.as_mut_ptr());
+    let tsr_invalid = TblisTensor::new(vec_b.as_mut_ptr() as *mut f64, &[3, 2], &[1, 3]);
+    assert!(matches!(tsr_invalid.as_complex_f(), Err(TblisError::InvalidLayout { .. })));
+}
+
+fn split_vec(data: &[c64]) -> (Vec<f64>, Vec<f64>) {
+    (data.iter().map(|x| x.re).collect(), data.iter().map(|x| x.im).collect())
+}
+
+fn merge_vec(re: &[f64], im: &[f64]) -> Vec<c64> {
+    re.iter().zip(im).map(|(&re, &im)| c64::new(re, im)).collect()
+}
+
+#[rstest]
+#[case(false, false)]
+#[case(true, false)]
+#[case(false, true)]
+#[case(true, true)]
+fn test_split_add_mult(#[case] conja: bool, #[case] conjb: bool) {
+    let (vec_a, tsr_a) = build_tblis_tensor("abc");
+    let (vec_b, tsr_b) = build_tblis_tensor("cd");
+    let (vec_c, mut tsr_c) = build_tblis_tensor("dab");
+    let (mut a_re, mut a_im) = split_vec(&vec_a);
+    let (mut b_re, mut b_im) = split_vec(&vec_b);
+    let (mut c_re, mut c_im) = split_vec(&vec_c);
+    let split_a = TblisSplitTensor::new(a_re.as_mut_ptr(), a_im.as_mut_ptr(), &tsr_a.shape, &tsr_a.stride);
+    let split_b = TblisSplitTensor::new(b_re.as_mut_ptr(), b_im.as_mut_ptr(), &tsr_b.shape, &tsr_b.stride);
+    let mut split_c = TblisSplitTensor::new(c_re.as_mut_ptr(), c_im.as_mut_ptr(), &tsr_c.shape, &tsr_c.stride);
+    let (alpha, beta) = (c64::new(0.7, -1.3), c64::new(-0.4, 0.9));
+
+    // mult
+    let cfg = TblisMultCfgBuilder::default().alpha(alpha).beta(beta).conja(conja).conjb(conjb).build().unwrap();
+    unsafe { tblis_tensor_mult(&tsr_a, "abc", &tsr_b, "cd", &mut tsr_c, "dab", Some(cfg.clone())) };
+    unsafe { tblis_split_tensor_mult(&split_a, "abc", &split_b, "cd", &mut split_c, "dab", Some(cfg)) };
+    assert!((fp(&merge_vec(&c_re, &c_im)) - fp(&vec_c)).abs() < 1e-10);
+
+    // add (with trace)
+    let (vec_d, mut tsr_d) = build_tblis_tensor("ba");
+    let (mut d_re, mut d_im) = split_vec(&vec_d);
+    let mut split_d = TblisSplitTensor::new(d_re.as_mut_ptr(), d_im.as_mut_ptr(), &tsr_d.shape, &tsr_d.stride);
+    let cfg = TblisAddCfgBuilder::default().alpha(alpha).beta(beta).conja(conja).conjb(conjb).build().unwrap();
+    unsafe { tblis_tensor_add(&tsr_a, "abc", &mut tsr_d, "ba", Some(cfg.clone())) };
+    unsafe { tblis_split_tensor_add(&split_a, "abc", &mut split_d, "ba", Some(cfg)) };
+    assert!((fp(&merge_vec(&d_re, &d_im)) - fp(&vec_d)).abs() < 1e-10);
+
+    // real alpha and zero beta
+    let cfg = TblisMultCfgBuilder::default().alpha(c64::new(2.0, 0.0)).conja(conja).build().unwrap();
+    unsafe { tblis_tensor_mult(&tsr_a, "abc", &tsr_b, "cd", &mut tsr_c, "dab", Some(cfg.clone())) };
+    unsafe { tblis_split_tensor_mult(&split_a, "abc", &split_b, "cd", &mut split_c, "dab", Some(cfg)) };
+    assert!((fp(&merge_vec(&c_re, &c_im)) - fp(&vec_c)).abs() < 1e-10);
+}
+
+#[rstest]
+#[case("ab,bc->ac")]
+#[case("a,ab,b->")]
+#[case("aab,bc->ca")]
+#[case("abcd->dcab")]
+#[case(",ab->ab")]
+#[case("ea,fb,abcd,gc,hd->efgh")]
+fn test_einsum_split(#[case] einsum_str: &str) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let mut tensors_split = tensors.iter().map(|(data, _)| split_vec(data)).collect::<Vec<_>>();
+    let split_tensors = tensors
+        .iter()
+        .zip(tensors_split.iter_mut())
+        .map(|((_, t), (re, im))| TblisSplitTensor::new(re.as_mut_ptr(), im.as_mut_ptr(), &t.shape, &t.stride))
+        .collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let split_tensors = split_tensors.iter().collect::<Vec<_>>();
+
+    let out_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, None, None).unwrap() };
+    let out = unsafe { tblis_einsum_split(einsum_str, &split_tensors, true, None, true, None, None).unwrap() };
+    assert_eq!(out.shape(), out_ref.shape());
+    assert!((fp(&merge_vec(out.re.as_slice(), out.im.as_slice())) - fp(out_ref.as_slice())).abs() < 1e-10);
+
+    // pre-allocated output with complex alpha and beta
+    let cfg = TblisEinsumCfgBuilder::default().alpha(c64::new(0.5, 1.5)).beta(c64::new(0.0, -1.0)).build().unwrap();
+    let mut vec_out = gen_array(out_ref.shape());
+    let (mut out_re, mut out_im) = split_vec(&vec_out);
+    let stride = build_strides(out_ref.shape());
+    let mut tsr_out = TblisTensor::new(vec_out.as_mut_ptr(), out_ref.shape(), &stride);
+    let mut split_out = TblisSplitTensor::new(out_re.as_mut_ptr(), out_im.as_mut_ptr(), out_ref.shape(), &stride);
+    unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, Some(&mut tsr_out), Some(cfg.clone())) };
+    unsafe { tblis_einsum_split(einsum_str, &split_tensors, true, None, true, Some(&mut split_out), Some(cfg)) };
+    assert!((fp(&merge_vec(&out_re, &out_im)) - fp(&vec_out)).abs() < 1e-10);
+
+    // memory budget
+    let shapes = tensors.iter().map(|(_, t)| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let plan = TblisEinsumPlan::<c64>::new(einsum_str, &shapes, true, None, true);
+    let peak = plan.peak_bytes(true);
+    let cfg = TblisEinsumCfgBuilder::default().memory_budget(peak).build().unwrap();
+    let out = unsafe { plan.execute_split_f(&split_tensors, None, Some(cfg)).unwrap().unwrap() };
+    assert!((fp(&merge_vec(out.re.as_slice(), out.im.as_slice())) - fp(out_ref.as_slice())).abs() < 1e-10);
+    let cfg = TblisEinsumCfgBuilder::default().memory_budget(peak - 1).build().unwrap();
+    let res = unsafe { plan.execute_split_f(&split_tensors, None, Some(cfg)) };
+    assert!(
+        matches!(res, Err(TblisError::MemoryBudget { required, budget, .. }) if (required, budget) == (peak, peak - 1))
+    );
+}
+
+#[test]
+fn test_split_allocator() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug, Default)]
+    struct CountingAllocator {
+        num_alloc: AtomicUsize,
+        current: Arc<AtomicUsize>,
+    }
+
+    impl TblisAllocator<c64> for CountingAllocator {
+        unsafe fn allocate(&self, size: usize) -> Result<TblisBuffer<c64>, TblisError> {
+            self.num_alloc.fetch_add(1, Ordering::Relaxed);
+            self.current.fetch_add(size, Ordering::Relaxed);
+            let current = self.current.clone();
+            let buffer = TblisBuffer::from_vec(vec![c64::new(f64::NAN, f64::NAN); size + 1], 1);
+            Ok(buffer.on_release(move || _ = current.fetch_sub(size, Ordering::Relaxed)))
+        }
+    }
+
+    let einsum_str = "aab,bc,cd,de->ae";
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let mut tensors_split = tensors.iter().map(|(data, _)| split_vec(data)).collect::<Vec<_>>();
+    let split_tensors = tensors
+        .iter()
+        .zip(tensors_split.iter_mut())
+        .map(|((_, t), (re, im))| TblisSplitTensor::new(re.as_mut_ptr(), im.as_mut_ptr(), &t.shape, &t.stride))
+        .collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let split_tensors = split_tensors.iter().collect::<Vec<_>>();
+    let out_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+
+    let allocator = Arc::new(CountingAllocator::default());
+    let cfg = TblisEinsumCfgBuilder::default().allocator(allocator.clone()).build().unwrap();
+    let out =
+        unsafe { tblis_einsum_split(einsum_str, &split_tensors, "optimal", None, true, None, Some(cfg)).unwrap() };
+    assert!((fp(&merge_vec(out.re.as_slice(), out.im.as_slice())) - fp(out_ref.as_slice())).abs() < 1e-10);
+    // intermediates (and traced operand) of both parts from allocator
+    assert!(allocator.num_alloc.load(Ordering::Relaxed) > 2);
+    // intermediates released, output kept by user; each complex element holds two real elements
+    assert_eq!(allocator.current.load(Ordering::Relaxed), 2 * out.re.size().div_ceil(2));
+    // output released to allocator when dropped by user
+    drop(out);
+    assert_eq!(allocator.current.load(Ordering::Relaxed), 0);
+
+    // temporary copy for complex beta (transpose has no intermediates) from allocator
+    let (num_alloc, current) = (allocator.num_alloc.load(Ordering::Relaxed), allocator.current.load(Ordering::Relaxed));
+    let shape_t = [tensors[1].1.shape[1], tensors[1].1.shape[0]];
+    let mut vec_t = gen_array(&shape_t);
+    let (mut t_re, mut t_im) = split_vec(&vec_t);
+    let stride_t = build_strides(&shape_t);
+    let mut tsr_t = TblisTensor::new(vec_t.as_mut_ptr(), &shape_t, &stride_t);
+    let mut split_t = TblisSplitTensor::new(t_re.as_mut_ptr(), t_im.as_mut_ptr(), &shape_t, &stride_t);
+    let cfg = TblisEinsumCfgBuilder::default().beta(c64::new(0.5, -1.0)).build().unwrap();
+    unsafe { tblis_einsum("bc->cb", &[tblis_tensors[1]], true, None, true, Some(&mut tsr_t), Some(cfg)) };
+    let cfg = TblisEinsumCfgBuilder::default().beta(c64::new(0.5, -1.0)).allocator(allocator.clone()).build().unwrap();
+    unsafe { tblis_einsum_split("bc->cb", &[split_tensors[1]], true, None, true, Some(&mut split_t), Some(cfg)) };
+    assert!((fp(&merge_vec(&t_re, &t_im)) - fp(&vec_t)).abs() < 1e-10);
+    assert_eq!(allocator.num_alloc.load(Ordering::Relaxed), num_alloc + 1);
+    assert_eq!(allocator.current.load(Ordering::Relaxed), current);
+
+    // index slicing is not performed, so memory ceiling of plan could not be honored
+    let shapes = tensors.iter().map(|(_, t)| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let plan = TblisEinsumPlan::<c64>::new(einsum_str, &shapes, "optimal", None, true);
+    let required = plan.largest_intermediate() * size_of::<c64>();
+    let plan = plan.with_memory_ceiling(size_of::<c64>());
+    assert!(plan.slicing.is_some());
+    let res = unsafe { plan.execute_split_f(&split_tensors, None, None) };
+    assert_eq!(res.err(), Some(TblisError::MemoryLimit { required, limit: size_of::<c64>() }));
+}
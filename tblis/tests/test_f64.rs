@@ -139,8 +139,947 @@ fn test_einsum(#[case] einsum_str: &str, #[case] ref_shape: Vec<isize>, #[case]
     let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
     let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
     let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
-    let (out_data, out_tensor) = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, None).unwrap() };
-    let out_fp = fp(&out_data);
-    assert_eq!(out_tensor.shape, ref_shape);
+    let out = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, None, None).unwrap() };
+    let out_fp = fp(out.as_slice());
+    assert_eq!(out.shape(), ref_shape);
     assert!((out_fp - ref_fp).abs() < 1e-10);
 }
+
+#[rstest]
+#[case("ea,fb,gc,hd,abcd->efgh"                , vec![4, 3, 2, 6]        ,  12.1332755429121821)]
+#[case("acdf,jbje,gihb,hfac,gfac,gifabc,hfac"  , vec![5, 4]              ,   0.1814690776365560)]
+#[case("ab,ab,cd,cd->ac"                       , vec![2, 4]              ,   5.1155948313305153)]
+#[case("dcc,fce,ea,dbf->ab"                    , vec![2, 3]              ,  -0.0995815485371659)]
+#[case("abcd->dcab"                            , vec![5, 4, 2, 3]        ,   5.0109395102756960)]
+fn test_einsum_plan(#[case] einsum_str: &str, #[case] ref_shape: Vec<isize>, #[case] ref_fp: f64) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let shapes = tblis_tensors.iter().map(|t| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let plan = TblisEinsumPlan::<f64>::new(einsum_str, &shapes, "optimal", None, true);
+    assert_eq!(plan.shape_out, ref_shape);
+    // plan is reusable
+    for _ in 0..2 {
+        let out = unsafe { plan.execute(&tblis_tensors, None, None).unwrap() };
+        assert_eq!(out.shape(), ref_shape);
+        assert!((fp(out.as_slice()) - ref_fp).abs() < 1e-10);
+    }
+    // operands of incompatible shapes should be rejected
+    let (_, tsr_wrong) = build_tblis_tensor("AAAAAAAA");
+    let mut tblis_tensors_wrong = tblis_tensors.clone();
+    tblis_tensors_wrong[0] = &tsr_wrong;
+    let err = unsafe { plan.execute_f(&tblis_tensors_wrong, None, None) }.unwrap_err();
+    assert!(matches!(err, TblisError::OperandShape { operand: 0, .. }));
+}
+
+#[test]
+fn test_einsum_ellipsis() {
+    // reference by explicit subscripts
+    let (vec_a, tsr_a) = build_tblis_tensor("cab");
+    let (_vec_b, tsr_b) = build_tblis_tensor("cbd");
+    let arr_ref = unsafe { tblis_einsum("cab,cbd->cad", &[&tsr_a, &tsr_b], true, None, true, None, None).unwrap() };
+
+    let arr_c = unsafe { tblis_einsum("...ab,...bd->...ad", &[&tsr_a, &tsr_b], true, None, true, None, None).unwrap() };
+    assert_eq!(arr_c.shape(), arr_ref.shape());
+    assert!((fp(arr_c.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+
+    // implicit output: ellipsis dimensions first
+    let arr_c = unsafe { tblis_einsum("...ab,...bd", &[&tsr_a, &tsr_b], true, None, true, None, None).unwrap() };
+    assert_eq!(arr_c.shape(), arr_ref.shape());
+    assert!((fp(arr_c.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+
+    // broadcasting of size-1 dimension, compared with replicated operand
+    let tsr_a_bcst = TblisTensor::new(vec_a.as_ptr() as *mut f64, &[1, 2, 3], &[6, 3, 1]);
+    let arr_c =
+        unsafe { tblis_einsum("...ab,...bd->...ad", &[&tsr_a_bcst, &tsr_b], true, None, true, None, None).unwrap() };
+    let arr_ref = unsafe {
+        tblis_einsum("ab,cbd->cad", &[&build_tblis_tensor("ab").1, &tsr_b], true, None, true, None, None).unwrap()
+    };
+    assert_eq!(arr_c.shape(), arr_ref.shape());
+    assert!((fp(arr_c.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+
+    // non-broadcastable dimensions
+    let tsr_a_wrong = TblisTensor::new(vec_a.as_ptr() as *mut f64, &[2, 1, 3], &[3, 3, 1]);
+    let err = unsafe { tblis_einsum_f("...ab,...bd->...ad", &[&tsr_a_wrong, &tsr_b], true, None, true, None, None) };
+    assert!(matches!(err, Err(TblisError::ShapeMismatch { .. })));
+}
+
+#[rstest]
+#[case("ab,bc->ac")]
+#[case("aab,bc->ac")]
+#[case("ab,ab,c->c")]
+#[case("a,->a")]
+#[case(",->")]
+fn test_einsum_accumulate(#[case] einsum_str: &str) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let arr_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, None, None).unwrap() };
+
+    // C = 2 * einsum(...) + 0.5 * C
+    let (mut vec_out, mut tsr_out) = build_tblis_tensor(einsum_str.split("->").nth(1).unwrap());
+    let vec_init = vec_out.clone();
+    tsr_out.data = vec_out.as_mut_ptr();
+    let cfg = TblisEinsumCfgBuilder::default().alpha(2.0).beta(0.5).build().unwrap();
+    let res = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, Some(&mut tsr_out), Some(cfg)) };
+    assert!(res.is_none());
+    for ((&out, &init), &r) in vec_out.iter().zip(&vec_init).zip(arr_ref.as_slice()) {
+        assert!((out - (2.0 * r + 0.5 * init)).abs() < 1e-10);
+    }
+}
+
+#[rstest]
+#[case("ab->ab")]
+#[case("ab->ba")]
+#[case("abc->cab")]
+#[case("aa->")]
+#[case("aab->b")]
+#[case(",->")]
+#[case("a,->a")]
+#[case("ab,bc->ca")]
+#[case("ab,ab,c->c")]
+fn test_einsum_out_strided(#[case] einsum_str: &str) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let arr_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, None, None).unwrap() };
+
+    // pre-allocated output with col-major and padded strides
+    let shape = arr_ref.shape().to_vec();
+    let mut stride = vec![2isize; shape.len()];
+    for i in 1..shape.len() {
+        stride[i] = stride[i - 1] * shape[i - 1];
+    }
+    let size = 2 * shape.iter().product::<isize>() as usize;
+    let mut vec_out = vec![0.0; size.max(1)];
+    let mut tsr_out = TblisTensor::new(vec_out.as_mut_ptr(), &shape, &stride);
+    let res = unsafe { tblis_einsum(einsum_str, &tblis_tensors, true, None, true, Some(&mut tsr_out), None) };
+    assert!(res.is_none());
+
+    for (i, &r) in arr_ref.as_slice().iter().enumerate() {
+        // row-major index of reference to offset of output
+        let mut rem = i as isize;
+        let mut offset = 0;
+        for d in (0..shape.len()).rev() {
+            offset += (rem % shape[d]) * stride[d];
+            rem /= shape[d];
+        }
+        assert!((vec_out[offset as usize] - r).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn test_errors() {
+    let (_vec_a, mut tsr_a) = build_tblis_tensor("ab");
+    let (_vec_b, mut tsr_b) = build_tblis_tensor("bc");
+
+    // inconsistent dimension size of the same index
+    let err = unsafe { tblis_tensor_add_f(&tsr_a, "ab", &mut tsr_b, "ab", None) }.unwrap_err();
+    assert!(matches!(err, TblisError::ShapeMismatch { index: 'a', expected: 2, found: 3, .. }));
+    // subscript length mismatch
+    let err = unsafe { tblis_tensor_scale_f(&mut tsr_a, "abc", None) }.unwrap_err();
+    assert!(matches!(err, TblisError::SubscriptLength { .. }));
+    // unique indices in both tensors
+    let err = unsafe { tblis_tensor_add_f(&tsr_a, "ab", &mut tsr_b, "bc", None) }.unwrap_err();
+    assert_eq!(err, TblisError::UniqueIndex {
+        func: "tblis_tensor_add",
+        subscripts: vec!["ab".into(), "bc".into()],
+        unique: vec!["a".into(), "c".into()]
+    });
+    // not a scalar
+    assert!(matches!(tsr_a.to_scalar(), Err(TblisError::NotScalar { .. })));
+    // path optimization (subscripts parsing) failure
+    let err = unsafe { tblis_einsum_f("ab,bc->ad", &[&tsr_a, &tsr_b], true, None, true, None, None) };
+    assert!(matches!(err, Err(TblisError::PathOptimization(_))));
+    // output shape mismatch
+    let mut vec_c = vec![0.0; 6];
+    let mut tsr_c = TblisTensor::new(vec_c.as_mut_ptr(), &[3, 2], &[2, 1]);
+    let err = unsafe { tblis_einsum_f("ab,bc->ac", &[&tsr_a, &tsr_b], true, None, true, Some(&mut tsr_c), None) };
+    assert!(matches!(err, Err(TblisError::OutputMismatch { .. })));
+    // too many labels
+    let labels = (0..200).map(|i| char::from_u32(0x4e00 + i).unwrap()).collect::<String>();
+    assert!(matches!(tblis::char_parse::char_parse(&[&labels]), Err(TblisError::TooManyLabels { count: 200, .. })));
+    // error is displayable and usable as `std::error::Error`
+    let err: Box<dyn std::error::Error> = Box::new(err.unwrap_err());
+    assert!(err.to_string().contains("Output tensor shape mismatch"));
+}
+
+#[test]
+fn test_views() {
+    let (vec_a, tsr_a) = build_tblis_tensor("ab");
+    let (vec_b, tsr_b) = build_tblis_tensor("bc");
+    let vec_ref =
+        unsafe { tblis_einsum("ab,bc->ac", &[&tsr_a, &tsr_b], true, None, true, None, None).unwrap() }.into_vec();
+
+    let view_a = TblisView::from_shape(&vec_a, &build_shape("ab"), true);
+    let view_b = TblisView::new(&vec_b, &build_shape("bc"), &build_strides(&build_shape("bc")));
+
+    // einsum on views
+    let arr_c = tblis_einsum_view("ab,bc->ac", &[&view_a, &view_b], true, None, true, None, None).unwrap();
+    assert!((fp(arr_c.as_slice()) - fp(&vec_ref)).abs() < 1e-10);
+
+    // einsum into mutable view
+    let mut vec_c = vec![0.0; vec_ref.len()];
+    let mut view_c = TblisViewMut::from_shape(&mut vec_c, &build_shape("ac"), true);
+    assert!(tblis_einsum_view("ab,bc->ac", &[&view_a, &view_b], true, None, true, Some(&mut view_c), None).is_none());
+    assert!((fp(&vec_c) - fp(&vec_ref)).abs() < 1e-10);
+
+    // tensor operations on views
+    let mut vec_c = vec![0.0; vec_ref.len()];
+    let mut view_c = TblisViewMut::from_shape(&mut vec_c, &build_shape("ac"), true);
+    tblis_view_mult(&view_a, "ab", &view_b, "bc", &mut view_c, "ac", None);
+    tblis_view_scale(&mut view_c, "ac", Some(TblisScaleCfgBuilder::default().alpha(2.0).build().unwrap()));
+    tblis_view_shift(&mut view_c, "ac", 1.0, Some(TblisShiftCfgBuilder::default().alpha(0.5).build().unwrap()));
+    let sum = tblis_view_reduce(&view_c.view(), "ac", TblisReduceOp::Sum, None);
+    let dot = tblis_view_dot(&view_c.view(), "ac", &view_c.view(), "ac", None);
+    assert!((sum - vec_ref.iter().map(|x| x + 1.0).sum::<f64>()).abs() < 1e-10);
+    assert!((dot - vec_ref.iter().map(|x| (x + 1.0).powi(2)).sum::<f64>()).abs() < 1e-10);
+    // elements written by mult (default beta = 0) are used by einsum on the same view
+    let mut vec_e = vec![0.0; vec_ref.len()];
+    let mut view_e = TblisViewMut::from_shape(&mut vec_e, &build_shape("ac"), true);
+    tblis_view_mult(&view_a, "ab", &view_b, "bc", &mut view_e, "ac", None);
+    assert_eq!((view_e.as_tensor().scalar, view_e.as_tensor().conj), (1.0, false));
+    let arr_e = tblis_einsum_view("ac->ca", &[&view_e.view()], true, None, false, None, None).unwrap();
+    assert!((fp(arr_e.as_slice()) - fp(&vec_ref)).abs() < 1e-10);
+    tblis_view_scale(&mut view_e, "ac", Some(TblisScaleCfgBuilder::default().alpha(2.0).build().unwrap()));
+    let arr_e = tblis_einsum_view("ac->ac", &[&view_e.view()], true, None, true, None, None).unwrap();
+    assert!((fp(arr_e.as_slice()) - 2.0 * fp(&vec_ref)).abs() < 1e-10);
+
+    tblis_view_set(&mut view_c, "ac", 3.0, None);
+    let mut vec_d = vec![0.0; vec_ref.len()];
+    let mut view_d = TblisViewMut::from_shape(&mut vec_d, &build_shape("ca"), true);
+    tblis_view_add(&view_c.view(), "ac", &mut view_d, "ca", None);
+    assert!(vec_c.iter().chain(vec_d.iter()).all(|&x| x == 3.0));
+
+    // out of bounds layout
+    let res = TblisView::new_f(&vec_a[1..], &build_shape("ab"), &build_strides(&build_shape("ab")));
+    assert!(matches!(res, Err(TblisError::InvalidLayout { .. })));
+    let res = TblisView::new_f(&vec_a, &[2, 3], &[-3, 1]);
+    assert!(matches!(res, Err(TblisError::InvalidLayout { .. })));
+    // broadcast (stride 0) is allowed for immutable view, but not for mutable view
+    assert!(TblisView::new_f(&vec_a, &[4, 3], &[0, 1]).is_ok());
+    let res = TblisViewMut::new_f(&mut vec_d, &[4, 3], &[0, 1]);
+    assert!(matches!(res, Err(TblisError::SelfOverlap { .. })));
+    let res = TblisViewMut::new_f(&mut vec_d, &[3, 3], &[2, 1]);
+    assert!(matches!(res, Err(TblisError::SelfOverlap { .. })));
+    assert!(TblisViewMut::new_f(&mut vec_d, &[3, 2], &[1, 3]).is_ok());
+    // overflow of offsets is not wrapped to an in-bounds layout
+    let res = TblisView::new_f(&vec_a, &[2, 2], &[isize::MAX, isize::MAX]);
+    assert!(matches!(res, Err(TblisError::InvalidLayout { .. })));
+    let res = TblisViewMut::new_f(&mut vec_d, &[3], &[isize::MAX]);
+    assert!(matches!(res, Err(TblisError::InvalidLayout { .. })));
+    let res = TblisViewMut::new_f(&mut vec_d, &[2, 2], &[isize::MIN, isize::MIN]);
+    assert!(matches!(res, Err(TblisError::InvalidLayout { .. })));
+    assert!(TblisTensor::new(vec_d.as_mut_ptr(), &[2, 3], &[isize::MAX, isize::MAX / 2 + 1]).is_self_overlapping());
+}
+
+#[test]
+fn test_tblis_array() {
+    // large arrays are aligned to 64 bytes
+    let arr = TblisArray::<f64>::zeros(&[16, 17], true);
+    assert_eq!(arr.as_slice().as_ptr() as usize % 64, 0);
+    assert_eq!(arr.as_slice().len(), 16 * 17);
+    assert!(arr.as_slice().iter().all(|&x| x == 0.0));
+
+    // construct from vector, and use as operand and output of einsum
+    let vec_a = gen_array(&[3, 4]);
+    let arr_a = TblisArray::from_vec(vec_a.clone(), &[3, 4], true);
+    assert_eq!(arr_a.stride(), &[4, 1]);
+    let mut arr_b = TblisArray::<f64>::zeros(&[3, 4], false);
+    tblis_einsum_view("ij->ij", &[&arr_a.view()], true, None, true, Some(&mut arr_b.view_mut()), None);
+    assert_eq!(arr_b.stride(), &[1, 3]);
+    let vec_b = arr_b.into_vec();
+    for (i, j) in (0..3).flat_map(|i| (0..4).map(move |j| (i, j))) {
+        assert_eq!(vec_b[i + 3 * j], vec_a[4 * i + j]);
+    }
+
+    let arr_c = unsafe { tblis_einsum("ij->ji", &[&arr_a.to_tblis_tensor()], true, None, false, None, None).unwrap() };
+    assert!(!arr_c.is_row_major());
+    assert_eq!(arr_c.shape(), &[4, 3]);
+    assert_eq!(arr_c.as_slice(), vec_a.as_slice());
+
+    // size mismatch
+    let res = TblisArray::from_vec_f(vec_a, &[3, 5], true);
+    assert!(matches!(res, Err(TblisError::InvalidLayout { .. })));
+    // size overflow
+    let res = unsafe { TblisArray::<f64>::uninit_f(&[isize::MAX, 3], true) };
+    assert!(matches!(res, Err(TblisError::InvalidLayout { .. })));
+
+    // clone keeps layout, and large arrays are still aligned
+    let arr_d = arr_c.clone();
+    assert_eq!((arr_d.shape(), arr_d.stride()), (arr_c.shape(), arr_c.stride()));
+    assert_eq!(arr_d.as_slice(), arr_c.as_slice());
+    assert_eq!(arr.clone().as_slice().as_ptr() as usize % 64, 0);
+}
+
+#[rstest]
+#[case(TblisReduceOp::Max, 7.0, [1, 0, 2])]
+#[case(TblisReduceOp::MaxAbs, 9.0, [0, 1, 1])]
+#[case(TblisReduceOp::Min, -9.0, [0, 1, 1])]
+#[case(TblisReduceOp::MinAbs, 0.5, [1, 1, 0])]
+fn test_reduce_with_index(#[case] op: TblisReduceOp, #[case] ref_val: f64, #[case] ref_index: [isize; 3]) {
+    let shape = [2, 2, 3];
+    let mut vals = vec![1.0; 12];
+    let mut set = |idx: [isize; 3], v: f64, stride: &[isize], offset: isize| {
+        vals[(offset + idx.iter().zip(stride).map(|(i, t)| i * t).sum::<isize>()) as usize] = v;
+    };
+    // col-major, with negative stride in the second dimension
+    let stride = [1, -2, 4];
+    let offset = 2;
+    set([1, 0, 2], 7.0, &stride, offset);
+    set([0, 1, 1], -9.0, &stride, offset);
+    set([1, 1, 0], 0.5, &stride, offset);
+    let tsr = TblisTensor::new(unsafe { vals.as_mut_ptr().offset(offset) }, &shape, &stride);
+    let (val, off, index) = unsafe { tblis_tensor_reduce_with_index(&tsr, "abc", op, None) };
+    assert_eq!(val, ref_val);
+    assert_eq!(index, ref_index);
+    assert_eq!(off, ref_index.iter().zip(stride).map(|(i, t)| i * t).sum::<isize>());
+
+    // value is consistent to reduction without index
+    assert_eq!(unsafe { tblis_tensor_reduce(&tsr, "abc", op, None) }, ref_val);
+}
+
+#[test]
+fn test_reduce_with_index_invalid() {
+    let (_vec_a, tsr_a) = build_tblis_tensor("ab");
+    let res = unsafe { tblis_tensor_reduce_with_index_f(&tsr_a, "ab", TblisReduceOp::Sum, None) };
+    assert!(matches!(res, Err(TblisError::UnsupportedReduction { op: TblisReduceOp::Sum, .. })));
+    let tsr_empty = TblisTensor::<f64>::new(std::ptr::null_mut(), &[0, 3], &[3, 1]);
+    let res = unsafe { tblis_tensor_reduce_with_index_f(&tsr_empty, "ab", TblisReduceOp::Max, None) };
+    assert!(res.is_err());
+}
+
+#[rstest]
+#[case("abc", "a")]
+#[case("abc", "ca")]
+#[case("abc", "")]
+#[case("abc", "bca")]
+#[case("aab", "ba")]
+fn test_reduce_partial(#[case] idx_a: &str, #[case] idx_b: &str) {
+    let (_vec_a, tsr_a) = build_tblis_tensor(idx_a);
+    let shape_b = build_shape(idx_b);
+    let stride_b = build_strides(&shape_b);
+    let size_b = shape_b.iter().product::<isize>() as usize;
+    for op in [TblisReduceOp::Sum, TblisReduceOp::Max, TblisReduceOp::MinAbs, TblisReduceOp::Norm2] {
+        let mut vec_b = vec![0.0; size_b];
+        let mut tsr_b = TblisTensor::new(vec_b.as_mut_ptr(), &shape_b, &stride_b);
+        unsafe { tblis_tensor_reduce_partial(&tsr_a, idx_a, op, &mut tsr_b, idx_b, None) };
+
+        // reference: full reduction of each slice, where slice is obtained by multiplying one-hot tensor
+        for (i, &val) in vec_b.iter().enumerate() {
+            let mut vec_onehot = vec![0.0; size_b];
+            vec_onehot[i] = 1.0;
+            let tsr_onehot = TblisTensor::new(vec_onehot.as_mut_ptr(), &shape_b, &stride_b);
+            let idx_c = idx_a.chars().filter(|c| !idx_b.contains(*c)).collect::<String>();
+            let subscripts = format!("{idx_a},{idx_b}->{idx_c}");
+            let arr_c = unsafe { tblis_einsum(&subscripts, &[&tsr_a, &tsr_onehot], true, None, true, None, None) };
+            let arr_c = arr_c.unwrap();
+            let tsr_c = arr_c.to_tblis_tensor();
+            let val_ref = unsafe { tblis_tensor_reduce(&tsr_c, &idx_c, op, None) };
+            assert!((val - val_ref).abs() < 1e-10);
+        }
+    }
+
+    // indices of output must be subset of input
+    let mut vec_b = vec![0.0; 2];
+    let mut tsr_b = TblisTensor::new(vec_b.as_mut_ptr(), &[2], &[1]);
+    let res = unsafe { tblis_tensor_reduce_partial_f(&tsr_a, idx_a, TblisReduceOp::Max, &mut tsr_b, "z", None) };
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_reduce_partial_many_outputs() {
+    // output of 64 x 24 elements (transposed), reduced in parallel chunks by 4 threads
+    let shape_a = [64, 24, 5];
+    let mut vec_a = gen_array(&shape_a);
+    let tsr_a = TblisTensor::new(vec_a.as_mut_ptr(), &shape_a, &build_strides(&shape_a));
+    let (shape_b, stride_b) = ([24, 64], [1, 24]);
+    let num_threads = tblis_get_num_threads();
+    tblis_set_num_threads(4);
+    for op in [TblisReduceOp::Max, TblisReduceOp::MinAbs, TblisReduceOp::Norm2] {
+        let mut vec_b = vec![0.0; 64 * 24];
+        let mut tsr_b = TblisTensor::new(vec_b.as_mut_ptr(), &shape_b, &stride_b);
+        unsafe { tblis_tensor_reduce_partial(&tsr_a, "ijk", op, &mut tsr_b, "ji", None) };
+        for (i, j) in (0..64).flat_map(|i| (0..24).map(move |j| (i, j))) {
+            let slice = &vec_a[(i * 24 + j) * 5..(i * 24 + j + 1) * 5];
+            let val_ref = match op {
+                TblisReduceOp::Max => slice.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                TblisReduceOp::MinAbs => slice.iter().map(|x| x.abs()).fold(f64::INFINITY, f64::min),
+                _ => slice.iter().map(|x| x * x).sum::<f64>().sqrt(),
+            };
+            assert!((vec_b[j + 24 * i] - val_ref).abs() < 1e-10);
+        }
+    }
+    tblis_set_num_threads(num_threads);
+}
+
+#[test]
+fn test_norm() {
+    let (vec_a, tsr_a) = build_tblis_tensor("abc");
+    let norm1 = unsafe { tblis_tensor_norm(&tsr_a, "abc", TblisNormKind::Norm1, None) };
+    let norm2 = unsafe { tblis_tensor_norm(&tsr_a, "abc", TblisNormKind::Norm2, None) };
+    let norminf = unsafe { tblis_tensor_norm(&tsr_a, "abc", TblisNormKind::NormInf, None) };
+    assert!((norm1 - vec_a.iter().map(|x| x.abs()).sum::<f64>()).abs() < 1e-10);
+    assert!((norm2 - vec_a.iter().map(|x| x * x).sum::<f64>().sqrt()).abs() < 1e-10);
+    assert!((norminf - vec_a.iter().map(|x| x.abs()).fold(0.0, f64::max)).abs() < 1e-10);
+
+    // scaled by |alpha|
+    let cfg = TblisReduceCfgBuilder::default().alpha(-2.0).build().unwrap();
+    let norm2_scaled = unsafe { tblis_tensor_norm(&tsr_a, "abc", TblisNormKind::Norm2, Some(cfg)) };
+    assert!((norm2_scaled - 2.0 * norm2).abs() < 1e-10);
+}
+
+#[test]
+fn test_output_scalar_kept() {
+    let (_vec_a, tsr_a) = build_tblis_tensor("ab");
+    let (_vec_b, tsr_b) = build_tblis_tensor("bc");
+    let (vec_c, mut tsr_c) = build_tblis_tensor("ac");
+    let (_vec_d, tsr_d) = build_tblis_tensor("ac");
+    let arr_ref = unsafe { tblis_einsum("ab,bc->ac", &[&tsr_a, &tsr_b], true, None, true, None, None).unwrap() };
+
+    // C = A B (beta = 0), then C is used as einsum operand
+    unsafe { tblis_tensor_mult(&tsr_a, "ab", &tsr_b, "bc", &mut tsr_c, "ac", None) };
+    assert_eq!((tsr_c.scalar, tsr_c.conj), (1.0, false));
+    let arr = unsafe { tblis_einsum("ac->ac", &[&tsr_c], true, None, true, None, None).unwrap() };
+    assert!((fp(arr.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+
+    // scale, shift and add do not change scalar of output either
+    let cfg = TblisScaleCfgBuilder::default().alpha(2.0).build().unwrap();
+    unsafe { tblis_tensor_scale(&mut tsr_c, "ac", Some(cfg)) };
+    let cfg = TblisShiftCfgBuilder::default().alpha(0.5).build().unwrap();
+    unsafe { tblis_tensor_shift(&mut tsr_c, "ac", 1.0, Some(cfg)) };
+    let cfg = TblisAddCfgBuilder::default().beta(0.5).build().unwrap();
+    unsafe { tblis_tensor_add(&tsr_d, "ac", &mut tsr_c, "ac", Some(cfg)) };
+    assert_eq!((tsr_c.scalar, tsr_c.conj), (1.0, false));
+    let arr = unsafe { tblis_einsum("ac->ac", &[&tsr_c], true, None, true, None, None).unwrap() };
+    assert_eq!(arr.as_slice(), vec_c.as_slice());
+}
+
+#[test]
+fn test_dot_reduce() {
+    let (mut vec_a, mut vec_b) = (vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], vec![0.5, -1.0, 2.0, 3.0, 1.5, -2.0]);
+    let tsr_a = TblisTensor::new(vec_a.as_mut_ptr(), &[2, 3], &[3, 1]);
+    let tsr_b = TblisTensor::new(vec_b.as_mut_ptr(), &[3, 2], &[2, 1]);
+    // A_ab B_ba, with B transposed
+    let dot = unsafe { tblis_tensor_dot(&tsr_a, "ab", &tsr_b, "ba", None) };
+    // (0.5 + 4.0 + 4.5) + (-4.0 + 15.0 - 12.0)
+    assert_eq!(dot, 8.0);
+
+    assert_eq!(unsafe { tblis_tensor_reduce(&tsr_a, "ab", TblisReduceOp::Sum, None) }, 21.0);
+    assert_eq!(unsafe { tblis_tensor_reduce(&tsr_b, "ab", TblisReduceOp::SumAbs, None) }, 10.0);
+    assert_eq!(unsafe { tblis_tensor_reduce(&tsr_b, "ab", TblisReduceOp::Max, None) }, 3.0);
+    let norm2 = unsafe { tblis_tensor_reduce(&tsr_a, "ab", TblisReduceOp::Norm2, None) };
+    assert!((norm2 - 91.0_f64.sqrt()).abs() < 1e-10);
+}
+
+#[test]
+fn test_einsum_path() {
+    let shapes = vec![vec![4, 5, 2], vec![2, 3], vec![3, 4, 6]];
+    let path = tblis_einsum_path("abc,cd,dae->be", &shapes, "optimal", None);
+    assert_eq!(path.steps, tblis_einsum_prep("abc,cd,dae->be", &shapes, "optimal", None));
+    assert_eq!(path.path.len(), 2);
+    assert!(path.naive_cost() >= path.opt_cost());
+    assert!((path.speedup() - path.naive_cost() / path.opt_cost()).abs() < 1e-10);
+    assert!(path.largest_intermediate() >= 24.0);
+    let report = path.to_string();
+    assert!(report.contains("Complete contraction"));
+    assert!(report.contains("abc,cd,dae->be"));
+
+    // ellipsis
+    let path = tblis_einsum_path("...ij,...jk->...ik", &[vec![3, 1, 2, 4], vec![5, 4, 6]], "optimal", None);
+    assert_eq!(path.steps.last().unwrap().shape_c, vec![3, 5, 2, 6]);
+
+    // invalid subscripts
+    assert!(tblis_einsum_path_f("ab,bc->ad", &[vec![2, 3], vec![3, 4]], "optimal", None).is_err());
+}
+
+#[test]
+fn test_explicit_path() {
+    let subscripts = "ab,bc,cd,de->ae";
+    let tensors = subscripts.split("->").next().unwrap().split(',').map(build_tblis_tensor).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let shapes = tensors.iter().map(|(_, t)| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let out_ref = unsafe { tblis_einsum(subscripts, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+
+    let path = vec![vec![2, 3], vec![1, 2], vec![0, 1]];
+    let plan = TblisEinsumPlan::<f64>::from_path(subscripts, &shapes, &path, true);
+    assert_eq!(plan.steps[0].contract.idx_a, "cd");
+    let out = unsafe { plan.execute(&tblis_tensors, None, None).unwrap() };
+    assert!((fp(out.as_slice()) - fp(out_ref.as_slice())).abs() < 1e-10);
+
+    let optimize = TblisExplicitPath(path.clone());
+    let out = unsafe { tblis_einsum(subscripts, &tblis_tensors, optimize, None, true, None, None).unwrap() };
+    assert!((fp(out.as_slice()) - fp(out_ref.as_slice())).abs() < 1e-10);
+    assert_eq!(tblis_einsum_path(subscripts, &shapes, TblisExplicitPath(path), None).path[0], vec![2, 3]);
+
+    // invalid paths
+    for path in
+        [vec![vec![], vec![0, 1, 2, 3]], vec![vec![1, 1], vec![0, 1], vec![0, 1]], vec![vec![0, 4]], vec![vec![0, 1]]]
+    {
+        let res = TblisEinsumPlan::<f64>::from_path_f(subscripts, &shapes, &path, true);
+        assert!(matches!(res, Err(TblisError::InvalidPath { .. })));
+        let res =
+            unsafe { tblis_einsum_f(subscripts, &tblis_tensors, TblisExplicitPath(path), None, true, None, None) };
+        assert!(res.is_err());
+    }
+}
+
+#[rstest]
+#[case(vec![vec![0, 1, 2], vec![0, 1]])]
+#[case(vec![vec![0, 2, 3], vec![0, 1]])]
+#[case(vec![vec![0, 1, 2, 3]])]
+#[case(vec![vec![1, 3], vec![0, 1, 2]])]
+fn test_multi_operand_step(#[case] path: Vec<Vec<usize>>) {
+    let subscripts = "ab,bc,cd,de->ae";
+    let tensors = subscripts.split("->").next().unwrap().split(',').map(build_tblis_tensor).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let shapes = tensors.iter().map(|(_, t)| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let out_ref = unsafe { tblis_einsum(subscripts, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+
+    let plan = TblisEinsumPlan::<f64>::from_path(subscripts, &shapes, &path, true);
+    assert_eq!(plan.steps.len(), 3);
+    let out = unsafe { plan.execute(&tblis_tensors, None, None).unwrap() };
+    assert!((fp(out.as_slice()) - fp(out_ref.as_slice())).abs() < 1e-10);
+
+    let out = unsafe { tblis_einsum(subscripts, &tblis_tensors, path.clone(), None, true, None, None).unwrap() };
+    assert!((fp(out.as_slice()) - fp(out_ref.as_slice())).abs() < 1e-10);
+    // path reports decomposed pairwise steps
+    let report = tblis_einsum_path(subscripts, &shapes, TblisExplicitPath(path), None);
+    assert_eq!(report.steps.len(), 3);
+    assert_eq!(report.path, report.steps.iter().map(|step| step.indices.clone()).collect::<Vec<_>>());
+    assert!(report.path.iter().all(|indices| indices.len() <= 2));
+}
+
+#[rstest]
+#[case("ab,bc,cd->ad", 4.0)]
+#[case("abc,cd,de->abe", 6.0)]
+#[case("aab,bc,cd->ad", 2.0)]
+#[case("ab,bc,ca->", 2.0)]
+#[case("ab,bc,cd,de->ae", 1.0)]
+#[case("iij,ik,kl->il", 1.0)]
+#[case("iij,ik,kl->l", 1.0)]
+fn test_einsum_sliced(#[case] einsum_str: &str, #[case] memory_limit: f64) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let shapes = tensors.iter().map(|(_, t)| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let arr_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+
+    let plan = TblisEinsumPlan::<f64>::new(einsum_str, &shapes, "optimal", memory_limit, true);
+    assert!(plan.slicing.is_some());
+    assert!(plan.largest_intermediate() <= memory_limit as usize);
+    // repeated indices are sliced at all of their occurrences
+    let slicing = plan.slicing.as_ref().unwrap();
+    for (subscript, axes) in einsum_str_inp.iter().zip(&slicing.axes) {
+        for (&(c, _), axes) in slicing.indices.iter().zip(axes) {
+            assert_eq!(axes.len(), subscript.matches(c).count());
+        }
+    }
+    let arr = unsafe { plan.execute(&tblis_tensors, None, None).unwrap() };
+    assert!((fp(arr.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+
+    // C = 2 * einsum(...) + 0.5 * C
+    let (mut vec_out, mut tsr_out) = build_tblis_tensor(einsum_str.split("->").nth(1).unwrap());
+    let vec_init = vec_out.clone();
+    tsr_out.data = vec_out.as_mut_ptr();
+    let cfg = TblisEinsumCfgBuilder::default().alpha(2.0).beta(0.5).build().unwrap();
+    let res = unsafe {
+        tblis_einsum(einsum_str, &tblis_tensors, "optimal", memory_limit, true, Some(&mut tsr_out), Some(cfg))
+    };
+    assert!(res.is_none());
+    for ((&out, &init), &r) in vec_out.iter().zip(&vec_init).zip(arr_ref.as_slice()) {
+        assert!((out - (2.0 * r + 0.5 * init)).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn test_einsum_sliced_errors() {
+    let shapes = [vec![2, 3], vec![3, 4], vec![4, 5]];
+    let plan = TblisEinsumPlan::<f64>::new("ab,bc,cd->ad", &shapes, "optimal", None, true);
+    assert!(plan.slicing.is_none());
+    // no slicing required
+    let plan = plan.with_memory_ceiling(1024);
+    assert!(plan.slicing.is_none());
+    // not possible to fit a single element
+    let res = plan.with_memory_ceiling_f(4);
+    assert!(matches!(res, Err(TblisError::MemoryLimit { required: 8, limit: 4 })));
+    // only explicit numeric limit performs slicing
+    let shapes = [vec![4], vec![5], vec![6]];
+    let path = TblisExplicitPath(vec![vec![0, 1], vec![0, 1]]);
+    let plan = TblisEinsumPlan::<f64>::new("a,b,c->abc", &shapes, path.clone(), "max-input", true);
+    assert!(plan.slicing.is_none());
+    assert_eq!(plan.largest_intermediate(), 20);
+    let plan = TblisEinsumPlan::<f64>::new("a,b,c->abc", &shapes, path, 6.0, true);
+    assert!(plan.slicing.is_some());
+}
+
+#[rstest]
+#[case("ab,bc,cd->ad", 1, 'b', 2)]
+#[case("ab,bc,cd->ad", 0, 'a', 1)]
+#[case("abc,cd,de->abe", 0, 'c', 3)]
+#[case("ab,bc,cd->ad", 2, 'd', 2)]
+#[case("aab,bc->ac", 1, 'c', 3)]
+#[case("ab,ab,c->c", 0, 'b', 2)]
+#[case("ba,aac->bc", 0, 'a', 1)]
+#[case("ab,bbc->ac", 0, 'b', 2)]
+fn test_einsum_stream(#[case] einsum_str: &str, #[case] pos: usize, #[case] index: char, #[case] batch: isize) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let arr_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+
+    let tsr_stream = tblis_tensors[pos].clone();
+    let axis = einsum_str_inp[pos].find(index).unwrap();
+    let stream = TblisStreamSpec { operand: pos, index, shape: tsr_stream.shape.clone() };
+    let mut operands = tblis_tensors.clone();
+    operands.remove(pos);
+    // produce chunks in reverse order
+    let producer = || {
+        let tsr_stream = tsr_stream.clone();
+        let size = tsr_stream.shape[axis];
+        let mut offsets = (0..size).step_by(batch as usize).collect::<Vec<_>>();
+        move || {
+            let offset = offsets.pop()?;
+            let mut chunk = tsr_stream.clone();
+            chunk.data = unsafe { chunk.data.offset(offset * chunk.stride[axis]) };
+            chunk.shape[axis] = batch.min(size - offset);
+            Some((offset, chunk))
+        }
+    };
+
+    let arr = unsafe {
+        tblis_einsum_stream(einsum_str, &operands, &stream, producer(), "optimal", None, true, None, None).unwrap()
+    };
+    assert_eq!(arr.shape(), arr_ref.shape());
+    assert!((fp(arr.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+
+    // memory limit of full shapes (not of chunks)
+    let arr = unsafe {
+        tblis_einsum_stream(einsum_str, &operands, &stream, producer(), "optimal", "max-input", true, None, None)
+            .unwrap()
+    };
+    assert!((fp(arr.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+
+    // C = 2 * einsum(...) + 0.5 * C
+    let (mut vec_out, mut tsr_out) = build_tblis_tensor(einsum_str.split("->").nth(1).unwrap());
+    let vec_init = vec_out.clone();
+    tsr_out.data = vec_out.as_mut_ptr();
+    let cfg = TblisEinsumCfgBuilder::default().alpha(2.0).beta(0.5).build().unwrap();
+    let res = unsafe {
+        tblis_einsum_stream(
+            einsum_str,
+            &operands,
+            &stream,
+            producer(),
+            "optimal",
+            None,
+            true,
+            Some(&mut tsr_out),
+            Some(cfg),
+        )
+    };
+    assert!(res.is_none());
+    for ((&out, &init), &r) in vec_out.iter().zip(&vec_init).zip(arr_ref.as_slice()) {
+        assert!((out - (2.0 * r + 0.5 * init)).abs() < 1e-10);
+    }
+    // output keeps its scalar, so it is read correctly by later einsum
+    assert_eq!(tsr_out.scalar, 1.0);
+    let idx_out = einsum_str.split("->").nth(1).unwrap();
+    let arr_out = unsafe { tblis_einsum(&format!("{idx_out}->{idx_out}"), &[&tsr_out], true, None, true, None, None) };
+    assert_eq!(arr_out.unwrap().as_slice(), vec_out.as_slice());
+
+    // output aliasing an operand is rejected before the operand is overwritten
+    let vec_prev = tensors[if pos == 0 { 1 } else { 0 }].0.clone();
+    let mut tsr_alias = tsr_out.clone();
+    tsr_alias.data = operands[0].data;
+    let res = unsafe {
+        let out = Some(&mut tsr_alias);
+        tblis_einsum_stream_f(einsum_str, &operands, &stream, producer(), "optimal", None, true, out, None)
+    };
+    assert_eq!(res.unwrap_err(), TblisError::Aliasing { operand: if pos == 0 { 1 } else { 0 } });
+    assert_eq!(tensors[if pos == 0 { 1 } else { 0 }].0, vec_prev);
+
+    // chunk out of bounds
+    let mut chunk = Some((1, tsr_stream.clone()));
+    let res = unsafe {
+        tblis_einsum_stream_f(einsum_str, &operands, &stream, || chunk.take(), "optimal", None, true, None, None)
+    };
+    assert!(matches!(res, Err(TblisError::OperandShape { .. })));
+}
+
+#[rstest]
+#[case("ab,bc,cd->ad", None)]
+#[case("ab,bc,cd,de->ae", None)]
+#[case("aab,bc,cdd->ad", None)]
+#[case("ab,ab,c->c", None)]
+#[case("abc,cd,de->abe", Some(6.0))]
+fn test_workspace(#[case] einsum_str: &str, #[case] memory_limit: Option<f64>) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let shapes = tensors.iter().map(|(_, t)| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let arr_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+
+    let plan = TblisEinsumPlan::<f64>::new(einsum_str, &shapes, "optimal", memory_limit, true);
+    // workspace sized in advance
+    let mut workspace = TblisWorkspace::for_plan(&plan);
+    let size = workspace.size();
+    assert!(size >= plan.workspace_size());
+    for _ in 0..2 {
+        let arr = unsafe { plan.execute_with_workspace(&tblis_tensors, None, None, &mut workspace).unwrap() };
+        assert!((fp(arr.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+        assert_eq!(workspace.size(), size);
+    }
+    // workspace sized by the first execution
+    let mut workspace = TblisWorkspace::new();
+    unsafe { plan.execute_with_workspace(&tblis_tensors, None, None, &mut workspace).unwrap() };
+    let (num_buffers, size) = (workspace.num_buffers(), workspace.size());
+    assert!(size >= plan.workspace_size());
+    let arr = unsafe { plan.execute_with_workspace(&tblis_tensors, None, None, &mut workspace).unwrap() };
+    assert!((fp(arr.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+    assert_eq!((workspace.num_buffers(), workspace.size()), (num_buffers, size));
+}
+
+#[rstest]
+#[case("ab,bc,cd->ad", None)]
+#[case("ab,bc,cd,de->ae", None)]
+#[case("aab,bc,cdd->ad", None)]
+#[case("abc,cd,de->abe", Some(6.0))]
+fn test_memory_budget(#[case] einsum_str: &str, #[case] memory_limit: Option<f64>) {
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let shapes = tensors.iter().map(|(_, t)| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let arr_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+
+    let plan = TblisEinsumPlan::<f64>::new(einsum_str, &shapes, "optimal", memory_limit, true);
+    let peak = plan.peak_bytes(true);
+    assert_eq!(peak, (plan.workspace_size() + arr_ref.size()) * 8);
+    assert_eq!(plan.peak_bytes(false), plan.workspace_size() * 8);
+
+    // budget just fits
+    let mut workspace = TblisWorkspace::new();
+    let cfg = TblisEinsumCfgBuilder::default().memory_budget(peak).build().unwrap();
+    let arr = unsafe { plan.execute_with_workspace_f(&tblis_tensors, None, Some(cfg), &mut workspace).unwrap() };
+    assert!((fp(arr.unwrap().as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+    assert!(workspace.peak_bytes() >= peak);
+    workspace.reset_peak();
+    assert_eq!(workspace.peak_bytes(), workspace.size() * 8);
+
+    // budget exceeded, fails before execution
+    let cfg = TblisEinsumCfgBuilder::default().memory_budget(peak - 1).build().unwrap();
+    let res = unsafe { plan.execute_f(&tblis_tensors, None, Some(cfg)) };
+    match res {
+        Err(TblisError::MemoryBudget { step, required, budget }) => {
+            assert!(step < plan.slicing.as_ref().map_or(&plan, |s| &s.plan).steps.len());
+            assert_eq!((required, budget), (peak, peak - 1));
+        },
+        _ => panic!("expected memory budget error"),
+    }
+    assert!(matches!(plan.check_memory_budget_f(0, false), Err(TblisError::MemoryBudget { step: 0, .. })));
+
+    // output not allocated internally
+    let mut arr_out = arr_ref.clone();
+    let mut tsr_out = arr_out.to_tblis_tensor_mut();
+    let cfg = TblisEinsumCfgBuilder::default().memory_budget(plan.peak_bytes(false)).build().unwrap();
+    unsafe { plan.execute_f(&tblis_tensors, Some(&mut tsr_out), Some(cfg)).unwrap() };
+    assert!((fp(arr_out.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+}
+
+#[cfg(feature = "debug_checks")]
+#[test]
+fn test_debug_checks() {
+    // fresh buffers are poisoned
+    let arr = unsafe { TblisArray::<f64>::uninit(&[3, 4], true) };
+    assert!(arr.as_slice().iter().all(|x| x.is_nan()));
+
+    // outputs are verified to be finite when inputs are finite
+    let einsum_str = "aab,bc,cd->ad";
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let arr = unsafe { tblis_einsum_f(einsum_str, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+    assert!(arr.unwrap().as_slice().iter().all(|x| x.is_finite()));
+
+    // non-finite inputs give non-finite outputs without error
+    let mut vec_a = vec![1.0; 4];
+    vec_a[0] = f64::INFINITY;
+    let tsr_a = TblisTensor::new(vec_a.as_mut_ptr(), &[2, 2], &[2, 1]);
+    let arr = unsafe { tblis_einsum_f("ij,jk->ik", &[&tsr_a, &tsr_a], "optimal", None, true, None, None).unwrap() };
+    assert!(arr.unwrap().as_slice().iter().any(|x| !x.is_finite()));
+}
+
+#[test]
+fn test_overlap() {
+    let mut vec_a: Vec<f64> = (0..12).map(|x| x as f64).collect();
+    let ptr = vec_a.as_mut_ptr();
+    let tsr_a = TblisTensor::new(ptr, &[3, 4], &[4, 1]);
+    let tsr_half = TblisTensor::new(ptr, &[3, 2], &[4, 1]);
+    let tsr_odd = TblisTensor::new(unsafe { ptr.add(1) }, &[3, 2], &[4, 2]);
+    let tsr_bcast = TblisTensor::new(ptr, &[3, 4], &[0, 1]);
+
+    // memory extent
+    assert_eq!(tsr_a.memory_extent(), Some(ptr as usize..ptr as usize + 96));
+    assert_eq!(tsr_odd.memory_extent(), Some(ptr as usize + 8..ptr as usize + 96));
+    assert_eq!(TblisTensor::new(ptr, &[3, 0], &[4, 1]).memory_extent(), None);
+    assert!(tsr_a.overlaps(&tsr_half) && tsr_half.overlaps(&tsr_odd));
+    assert!(!tsr_bcast.overlaps(&TblisTensor::new(unsafe { ptr.add(4) }, &[2, 4], &[4, 1])));
+    assert!(tsr_bcast.is_self_overlapping() && !tsr_odd.is_self_overlapping());
+    // interleaved without common elements (even and odd columns, column blocks)
+    let tsr_even = TblisTensor::new(ptr, &[3, 2], &[4, 2]);
+    assert!(!tsr_even.overlaps(&tsr_odd) && !tsr_odd.overlaps(&tsr_even));
+    assert!(!tsr_half.overlaps(&TblisTensor::new(unsafe { ptr.add(2) }, &[3, 2], &[4, 1])));
+    assert!(tsr_half.overlaps(&TblisTensor::new(unsafe { ptr.add(1) }, &[3, 2], &[4, 1])));
+    // partially overlapping elements of different types
+    assert!(tsr_a.overlaps(&TblisTensor::new(unsafe { (ptr as *mut f32).add(1) }, &[1], &[1])));
+
+    // output overlaps with input
+    let mut vec_b = vec![0.0; 12];
+    let tsr_b = TblisTensor::new(vec_b.as_mut_ptr(), &[3, 4], &[4, 1]);
+    let res = unsafe { tblis_tensor_add_f(&tsr_a, "ij", &mut tsr_a.clone(), "ij", None) };
+    assert_eq!(res, Err(TblisError::Aliasing { operand: 0 }));
+    let tsr_sq = TblisTensor::new(ptr, &[2, 2], &[4, 1]);
+    let mut tsr_sq_out = TblisTensor::new(unsafe { ptr.add(1) }, &[2, 2], &[4, 1]);
+    let tsr_b_sq = TblisTensor::new(vec_b.as_mut_ptr(), &[2, 2], &[2, 1]);
+    let res = unsafe { tblis_tensor_mult_f(&tsr_b_sq, "ij", &tsr_sq, "jk", &mut tsr_sq_out, "ik", None) };
+    assert_eq!(res, Err(TblisError::Aliasing { operand: 1 }));
+
+    // self-overlapping output
+    let res = unsafe { tblis_tensor_add_f(&tsr_b, "ij", &mut tsr_bcast.clone(), "ij", None) };
+    assert!(matches!(res, Err(TblisError::SelfOverlap { .. })));
+    let res = unsafe { tblis_tensor_scale_f(&mut tsr_bcast.clone(), "ij", None) };
+    assert!(matches!(res, Err(TblisError::SelfOverlap { .. })));
+    let res = unsafe { tblis_tensor_set_f(&mut tsr_bcast.clone(), "ij", 1.0, None) };
+    assert!(matches!(res, Err(TblisError::SelfOverlap { .. })));
+    let res = unsafe { tblis_tensor_shift_f(&mut tsr_bcast.clone(), "ij", 1.0, None) };
+    assert!(matches!(res, Err(TblisError::SelfOverlap { .. })));
+
+    // einsum output
+    let res =
+        unsafe { tblis_einsum_f("ij,jk->ik", &[&tsr_b_sq, &tsr_sq], true, None, true, Some(&mut tsr_sq_out), None) };
+    assert_eq!(res.unwrap_err(), TblisError::Aliasing { operand: 1 });
+    let res = unsafe { tblis_einsum_f("ij->ij", &[&tsr_b], true, None, true, Some(&mut tsr_bcast.clone()), None) };
+    assert!(matches!(res, Err(TblisError::SelfOverlap { .. })));
+
+    // opt-out: in-place scaling by add of the same tensor
+    let cfg = TblisAddCfgBuilder::default().alpha(1.0).beta(1.0).check_overlap(false).build().unwrap();
+    unsafe { tblis_tensor_add_f(&tsr_a, "ij", &mut tsr_a.clone(), "ij", Some(cfg)).unwrap() };
+    assert_eq!(vec_a, (0..12).map(|x| 2.0 * x as f64).collect::<Vec<_>>());
+    let cfg = TblisSetCfgBuilder::default().check_overlap(false).build().unwrap();
+    unsafe { tblis_tensor_set_f(&mut tsr_bcast.clone(), "ij", 1.0, Some(cfg)).unwrap() };
+    assert_eq!(&vec_a[..4], &[1.0; 4]);
+
+    // interleaved output (columns 2..4 of the same rows as input) is allowed
+    vec_b.fill(1.0);
+    let tsr_b_sq = TblisTensor::new(vec_b.as_mut_ptr(), &[2, 2], &[2, 1]);
+    let mut tsr_sq_out = TblisTensor::new(unsafe { ptr.add(2) }, &[2, 2], &[4, 1]);
+    let operands = [&tsr_b_sq, &tsr_sq];
+    unsafe { tblis_einsum_f("ij,jk->ik", &operands, true, None, true, Some(&mut tsr_sq_out), None).unwrap() };
+    assert_eq!([vec_a[2], vec_a[3], vec_a[6], vec_a[7]], [9.0, 11.0, 9.0, 11.0]);
+}
+
+#[test]
+fn test_allocator() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug, Default)]
+    struct CountingAllocator {
+        num_alloc: AtomicUsize,
+        current: Arc<AtomicUsize>,
+    }
+
+    impl TblisAllocator<f64> for CountingAllocator {
+        unsafe fn allocate(&self, size: usize) -> Result<TblisBuffer<f64>, TblisError> {
+            self.num_alloc.fetch_add(1, Ordering::Relaxed);
+            self.current.fetch_add(size, Ordering::Relaxed);
+            let current = self.current.clone();
+            let buffer = TblisBuffer::from_vec(vec![f64::NAN; size + 1], 1);
+            Ok(buffer.on_release(move || _ = current.fetch_sub(size, Ordering::Relaxed)))
+        }
+    }
+
+    let einsum_str = "aab,bc,cd,de->ae";
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let shapes = tensors.iter().map(|(_, t)| t.shape.iter().map(|&s| s as usize).collect()).collect::<Vec<_>>();
+    let arr_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+
+    // allocator by configuration
+    let allocator = Arc::new(CountingAllocator::default());
+    let cfg = TblisEinsumCfgBuilder::default().allocator(allocator.clone()).build().unwrap();
+    let arr = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, Some(cfg)).unwrap() };
+    assert!((fp(arr.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+    assert!(allocator.num_alloc.load(Ordering::Relaxed) > 1);
+    // intermediates released, output kept by user
+    assert_eq!(allocator.current.load(Ordering::Relaxed), arr.size());
+    // output released to allocator when dropped by user
+    drop(arr);
+    assert_eq!(allocator.current.load(Ordering::Relaxed), 0);
+
+    // output converted to vector (no copy) is also released
+    let allocator = Arc::new(CountingAllocator::default());
+    let cfg = TblisEinsumCfgBuilder::default().allocator(allocator.clone()).build().unwrap();
+    let arr = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, Some(cfg)).unwrap() };
+    let vec = arr.into_vec();
+    assert!((fp(&vec) - fp(arr_ref.as_slice())).abs() < 1e-10);
+    assert_eq!(allocator.current.load(Ordering::Relaxed), 0);
+
+    // allocator of workspace
+    let allocator = Arc::new(CountingAllocator::default());
+    let plan = TblisEinsumPlan::<f64>::new(einsum_str, &shapes, "optimal", None, true);
+    let mut workspace = TblisWorkspace::with_allocator(allocator.clone());
+    let (mut vec_out, mut tsr_out) = build_tblis_tensor("ae");
+    tsr_out.data = vec_out.as_mut_ptr();
+    unsafe { plan.execute_with_workspace(&tblis_tensors, Some(&mut tsr_out), None, &mut workspace) };
+    let num_alloc = allocator.num_alloc.load(Ordering::Relaxed);
+    assert_eq!(num_alloc, workspace.num_buffers());
+    unsafe { plan.execute_with_workspace(&tblis_tensors, Some(&mut tsr_out), None, &mut workspace) };
+    assert_eq!(allocator.num_alloc.load(Ordering::Relaxed), num_alloc);
+    assert!((fp(&vec_out) - fp(arr_ref.as_slice())).abs() < 1e-10);
+    drop(workspace);
+    assert_eq!(allocator.current.load(Ordering::Relaxed), 0);
+
+    // workspace sized for plan by allocator
+    let allocator = Arc::new(CountingAllocator::default());
+    let mut workspace = TblisWorkspace::for_plan_in(&plan, allocator.clone());
+    let num_alloc = allocator.num_alloc.load(Ordering::Relaxed);
+    assert_eq!(num_alloc, workspace.num_buffers());
+    assert_eq!(allocator.current.load(Ordering::Relaxed), plan.workspace_size());
+    unsafe { plan.execute_with_workspace(&tblis_tensors, Some(&mut tsr_out), None, &mut workspace) };
+    assert_eq!(allocator.num_alloc.load(Ordering::Relaxed), num_alloc);
+    drop(workspace);
+    assert_eq!(allocator.current.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn test_huge_page_allocator() {
+    use std::sync::Arc;
+
+    let allocator = TblisHugePageAllocatorBuilder::default().threshold(1024).first_touch(true).build().unwrap();
+    let buffer = unsafe { TblisAllocator::<f64>::allocate(&allocator, 1 << 18).unwrap() };
+    assert!(buffer.len() >= 1 << 18);
+    if cfg!(target_os = "linux") {
+        // mapping is aligned to huge pages, whether or not `madvise` succeeds
+        assert_eq!(buffer.as_ptr() as usize % (1 << 21), 0);
+        assert!(buffer.as_slice().iter().all(|&x| x == 0.0));
+        // huge page storage is copied to vector
+        let vec = TblisBuffer::into_vec(buffer);
+        assert_eq!(vec.len(), 1 << 18);
+    }
+    // below threshold
+    let buffer = unsafe { TblisAllocator::<f64>::allocate(&allocator, 16).unwrap() };
+    assert_eq!(buffer.len(), 16);
+
+    let einsum_str = "abc,cd,de->abe";
+    let einsum_str_inp = einsum_str.split("->").next().unwrap().split(',').collect::<Vec<&str>>();
+    let tensors = einsum_str_inp.iter().map(|s| build_tblis_tensor(s)).collect::<Vec<_>>();
+    let tblis_tensors = tensors.iter().map(|(_, t)| t).collect::<Vec<_>>();
+    let arr_ref = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, None).unwrap() };
+    let allocator = TblisHugePageAllocatorBuilder::default().threshold(64).build().unwrap();
+    let cfg = TblisEinsumCfgBuilder::default().allocator(Arc::new(allocator)).build().unwrap();
+    let arr = unsafe { tblis_einsum(einsum_str, &tblis_tensors, "optimal", None, true, None, Some(cfg)).unwrap() };
+    assert!((fp(arr.as_slice()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+    assert!((fp(&arr.into_vec()) - fp(arr_ref.as_slice())).abs() < 1e-10);
+}